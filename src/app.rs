@@ -1,5 +1,6 @@
-use gpui::{AppContext, Entity};
+use gpui::{AppContext, Entity, Window};
 
+use crate::session::Session;
 use crate::workspace::Workspace;
 
 /// App 可以管理多个 Workspace，目前简化实现只支持一个
@@ -8,9 +9,12 @@ pub struct App {
 }
 
 impl App {
-  /// 创建一个新的 App，包含一个默认的 Workspace
-  pub fn new(cx: &mut gpui::Context<Self>) -> Self {
-    let workspace = cx.new(|cx| Workspace::new(cx));
+  /// 创建一个新的 App，从保存的会话恢复 Workspace，没有会话时创建默认的 Workspace
+  pub fn new(session: Option<&Session>, window: &mut Window, cx: &mut gpui::Context<Self>) -> Self {
+    let workspace = cx.new(|cx| match session {
+      Some(session) => Workspace::from_session(session, window, cx),
+      None => Workspace::new(window, cx),
+    });
 
     Self { workspace }
   }
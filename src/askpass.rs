@@ -0,0 +1,287 @@
+use std::ffi::OsString;
+use std::io::{Read, Write};
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use gpui::*;
+use gpui_component::input::{Input, InputState};
+use gpui_component::{ActiveTheme as _, Root, WindowExt as _};
+
+use crate::main_view::MainView;
+use crate::settings::Settings;
+use crate::terminal::TerminalView;
+
+/// 以密码助手模式运行的命令行参数
+pub const FLAG: &str = "--askpass";
+/// 终端中指向应用监听 socket 的环境变量
+const SOCKET_ENV: &str = "CATUS_ASKPASS_SOCKET";
+/// 助手发来的请求上限，提示只有一行，超过时视为异常连接
+const MAX_REQUEST_LEN: u64 = 4096;
+/// 助手写入请求的时限，避免卡住的连接一直占用线程
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 密码请求，`reply` 为 None 表示用户取消
+struct Request {
+  /// 发起请求的助手所在的会话，即终端 shell 的进程 ID
+  session: Option<u32>,
+  prompt: String,
+  reply: mpsc::Sender<Option<String>>,
+}
+
+/// 应用内的密码对话框端点
+///
+/// 启用时在只有当前用户可访问的目录下创建 Unix socket 和助手脚本，
+/// 终端中的 sudo -A 和 ssh 通过 `SUDO_ASKPASS`/`SSH_ASKPASS` 调用脚本，
+/// 脚本以 `--askpass` 启动本程序连接 socket，由应用弹出对话框输入密码，密码不会回显到终端。
+pub struct Askpass {
+  dir: PathBuf,
+  socket: PathBuf,
+  script: PathBuf,
+}
+
+impl Global for Askpass {}
+
+impl Askpass {
+  /// 新建终端时附加的环境变量，未启用时为空
+  pub fn terminal_env(cx: &App) -> Vec<(String, OsString)> {
+    let Some(askpass) = cx.try_global::<Self>() else {
+      return Vec::new();
+    };
+    vec![
+      ("SUDO_ASKPASS".into(), askpass.script.clone().into()),
+      ("SSH_ASKPASS".into(), askpass.script.clone().into()),
+      // 即使有终端也优先使用助手，OpenSSH 8.4 起支持
+      ("SSH_ASKPASS_REQUIRE".into(), "prefer".into()),
+      (SOCKET_ENV.into(), askpass.socket.clone().into()),
+    ]
+  }
+
+  fn create(requests: async_channel::Sender<Request>) -> std::io::Result<Self> {
+    let base = dirs::runtime_dir().unwrap_or_else(std::env::temp_dir);
+    let dir = base.join(format!("catus-askpass-{}", std::process::id()));
+    if dir.exists() {
+      std::fs::remove_dir_all(&dir)?;
+    }
+    std::fs::DirBuilder::new().mode(0o700).create(&dir)?;
+
+    let socket = dir.join("socket");
+    let listener = UnixListener::bind(&socket)?;
+
+    let script = dir.join("askpass");
+    let exe = std::env::current_exe()?;
+    let mut file = std::fs::OpenOptions::new()
+      .write(true)
+      .create_new(true)
+      .mode(0o700)
+      .open(&script)?;
+    writeln!(
+      file,
+      "#!/bin/sh\nexec '{}' {} \"$@\"",
+      exe.display().to_string().replace('\'', r"'\''"),
+      FLAG
+    )?;
+
+    thread::spawn(move || {
+      for stream in listener.incoming() {
+        match stream {
+          Ok(stream) => {
+            let requests = requests.clone();
+            // 每个连接独立等待用户输入，互不阻塞
+            thread::spawn(move || {
+              if let Err(e) = serve(stream, &requests) {
+                eprintln!("Askpass request failed: {}", e);
+              }
+            });
+          }
+          Err(e) => eprintln!("Askpass listener failed: {}", e),
+        }
+      }
+    });
+
+    Ok(Self {
+      dir,
+      socket,
+      script,
+    })
+  }
+}
+
+/// 读取一个连接的提示，等待用户输入后回复
+///
+/// 协议：助手写入所在会话的 ID 和换行，再写入提示后关闭写端；应用回复 `OK <密码>` 或 `CANCEL`。
+fn serve(mut stream: UnixStream, requests: &async_channel::Sender<Request>) -> std::io::Result<()> {
+  stream.set_read_timeout(Some(READ_TIMEOUT))?;
+  let mut message = String::new();
+  (&mut stream)
+    .take(MAX_REQUEST_LEN + 1)
+    .read_to_string(&mut message)?;
+  if message.len() as u64 > MAX_REQUEST_LEN {
+    return Err(std::io::Error::new(
+      std::io::ErrorKind::InvalidData,
+      "request too large",
+    ));
+  }
+  let (session, prompt) = message.split_once('\n').unwrap_or(("", &message));
+
+  let (reply, reply_rx) = mpsc::channel();
+  let request = Request {
+    session: session.parse().ok(),
+    prompt: prompt.trim_end().to_string(),
+    reply,
+  };
+  if requests.send_blocking(request).is_err() {
+    return stream.write_all(b"CANCEL\n");
+  }
+  match reply_rx.recv().ok().flatten() {
+    Some(password) => stream.write_all(format!("OK {}\n", password).as_bytes()),
+    None => stream.write_all(b"CANCEL\n"),
+  }
+}
+
+/// 找到 shell 进程为 `session` 的终端所在的窗口和 Tab 标题
+fn find_requester(session: u32, cx: &App) -> Option<(AnyWindowHandle, SharedString)> {
+  cx.windows().into_iter().find_map(|window| {
+    let root = window.downcast::<Root>()?.read(cx).ok()?;
+    let main_view = root.view().clone().downcast::<MainView>().ok()?;
+    let workspace = main_view.read(cx).workspace.read(cx);
+    workspace.tabs.iter().find_map(|tab| {
+      let view = tab.downcast::<TerminalView>()?;
+      (view.read(cx).terminal().read(cx).process_id() == Some(session))
+        .then(|| (window, tab.state.read(cx).title.clone()))
+    })
+  })
+}
+
+/// 在发起请求的终端所在窗口弹出密码对话框，找不到时使用当前窗口
+fn show_dialog(request: Request, cx: &mut App) {
+  let requester = request
+    .session
+    .and_then(|session| find_requester(session, cx));
+  let window = match &requester {
+    Some((window, _)) => Some(*window),
+    None => cx.active_window().or_else(|| cx.windows().first().copied()),
+  };
+  let Some(window) = window else {
+    let _ = request.reply.send(None);
+    return;
+  };
+  let requester: SharedString = match requester {
+    Some((_, title)) => format!("Requested by \"{}\"", title).into(),
+    None => "Requested by an unknown terminal".into(),
+  };
+
+  let reply = request.reply;
+  let prompt: SharedString = if request.prompt.is_empty() {
+    "Password:".into()
+  } else {
+    request.prompt.into()
+  };
+  let _ = window.update(cx, move |_, window, cx| {
+    let input = cx.new(|cx| InputState::new(window, cx).masked(true));
+    input.update(cx, |input, cx| input.focus(window, cx));
+    // 确认和关闭都会触发回复，只有第一次有效
+    let reply = std::rc::Rc::new(std::cell::Cell::new(Some(reply)));
+
+    window.open_dialog(cx, move |dialog, _window, cx| {
+      let input_for_ok = input.clone();
+      let reply_for_ok = reply.clone();
+      let reply_for_close = reply.clone();
+      dialog
+        .title("Password Required")
+        .w(px(360.))
+        .child(
+          div()
+            .text_sm()
+            .text_color(cx.theme().muted_foreground)
+            .child(requester.clone()),
+        )
+        .child(prompt.clone())
+        .child(Input::new(&input))
+        .confirm()
+        .on_ok(move |_, _window, cx| {
+          if let Some(reply) = reply_for_ok.take() {
+            let _ = reply.send(Some(input_for_ok.read(cx).value().to_string()));
+          }
+          true
+        })
+        .on_close(move |_, _window, _cx| {
+          if let Some(reply) = reply_for_close.take() {
+            let _ = reply.send(None);
+          }
+        })
+    });
+  });
+}
+
+/// 设置中启用时启动密码对话框端点；失败时终端照常工作，只是不设置助手
+pub fn init(cx: &mut App) {
+  if !cx.global::<Settings>().askpass {
+    return;
+  }
+  let (requests, requests_rx) = async_channel::unbounded::<Request>();
+  let askpass = match Askpass::create(requests) {
+    Ok(askpass) => askpass,
+    Err(e) => {
+      eprintln!("Failed to start askpass helper: {}", e);
+      return;
+    }
+  };
+
+  cx.spawn(async move |cx| {
+    while let Ok(request) = requests_rx.recv().await {
+      if cx.update(|cx| show_dialog(request, cx)).is_err() {
+        break;
+      }
+    }
+  })
+  .detach();
+
+  cx.on_app_quit(|cx| {
+    if let Some(askpass) = cx.try_global::<Askpass>() {
+      let _ = std::fs::remove_dir_all(&askpass.dir);
+    }
+    async {}
+  })
+  .detach();
+
+  cx.set_global(askpass);
+}
+
+/// 助手模式：把提示发给应用，输出用户输入的密码
+///
+/// 返回进程退出码，取消或无法连接时返回 1，sudo 和 ssh 会视为认证失败。
+pub fn run(mut args: impl Iterator<Item = String>) -> i32 {
+  let prompt = args.next().unwrap_or_default();
+  let Some(socket) = std::env::var_os(SOCKET_ENV) else {
+    eprintln!("{} is not set; run this from a catus terminal", SOCKET_ENV);
+    return 1;
+  };
+  match request_password(Path::new(&socket), &prompt) {
+    Ok(Some(password)) => {
+      println!("{}", password);
+      0
+    }
+    Ok(None) => 1,
+    Err(e) => {
+      eprintln!("Failed to reach catus: {}", e);
+      1
+    }
+  }
+}
+
+fn request_password(socket: &Path, prompt: &str) -> std::io::Result<Option<String>> {
+  let mut stream = UnixStream::connect(socket)?;
+  // 终端的 shell 是会话首进程，应用据此找到发起请求的终端
+  let session = unsafe { libc::getsid(0) };
+  stream.write_all(format!("{}\n{}", session, prompt).as_bytes())?;
+  stream.shutdown(std::net::Shutdown::Write)?;
+
+  let mut response = String::new();
+  stream.read_to_string(&mut response)?;
+  let response = response.strip_suffix('\n').unwrap_or(&response);
+  Ok(response.strip_prefix("OK ").map(str::to_string))
+}
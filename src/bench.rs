@@ -0,0 +1,346 @@
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use alacritty_terminal::event::VoidListener;
+use alacritty_terminal::term::{Config, Term};
+use alacritty_terminal::vte::ansi::{Processor, StdSyncHandler};
+use serde::Serialize;
+
+use crate::terminal::encoding::{OutputDecoder, TerminalEncoding};
+use crate::terminal::highlight::{HighlightRule, HighlightScanner};
+use crate::terminal::terminal::{DEFAULT_SCROLL_HISTORY_LINES, TermDimensions};
+use crate::terminal::{Terminal, TerminalElement, TerminalSize};
+
+/// 命令行参数
+pub const FLAG: &str = "--bench-render";
+
+const USAGE: &str = "\
+Usage: catus --bench-render [OPTIONS] [FILE...]
+
+Replays recorded terminal output (raw PTY bytes, e.g. from `script`) through the
+terminal core and grid layout without opening a window, and prints throughput and
+per-frame latency. Without FILE, built-in synthetic workloads are used.
+
+Options:
+  --cols <N>         Grid columns (default 80)
+  --rows <N>         Grid rows (default 24)
+  --chunk <BYTES>    Bytes fed per frame, like one PTY read (default 4096)
+  --iterations <N>   Times each workload is replayed (default 3)
+  --json             Print results as JSON
+  -h, --help         Show this help";
+
+/// 合成负载的大小
+const SYNTHETIC_SIZE: usize = 4 * 1024 * 1024;
+
+struct Options {
+  cols: u16,
+  rows: u16,
+  chunk: usize,
+  iterations: usize,
+  json: bool,
+  files: Vec<PathBuf>,
+}
+
+impl Default for Options {
+  fn default() -> Self {
+    Self {
+      cols: 80,
+      rows: 24,
+      chunk: 4096,
+      iterations: 3,
+      json: false,
+      files: Vec::new(),
+    }
+  }
+}
+
+impl Options {
+  /// 解析 `--bench-render` 之后的参数；`Ok(None)` 表示只打印帮助
+  fn parse(args: impl Iterator<Item = String>) -> Result<Option<Self>, String> {
+    fn value<T: std::str::FromStr>(
+      flag: &str,
+      args: &mut impl Iterator<Item = String>,
+    ) -> Result<T, String> {
+      let value = args
+        .next()
+        .ok_or_else(|| format!("{} requires a value", flag))?;
+      value
+        .parse()
+        .ok()
+        .filter(|_| !value.starts_with('-'))
+        .ok_or_else(|| format!("Invalid value for {}: {}", flag, value))
+    }
+
+    let mut options = Self::default();
+    let mut args = args;
+    while let Some(arg) = args.next() {
+      match arg.as_str() {
+        "--cols" => options.cols = value(&arg, &mut args)?,
+        "--rows" => options.rows = value(&arg, &mut args)?,
+        "--chunk" => options.chunk = value(&arg, &mut args)?,
+        "--iterations" => options.iterations = value(&arg, &mut args)?,
+        "--json" => options.json = true,
+        "-h" | "--help" => return Ok(None),
+        flag if flag.starts_with('-') => return Err(format!("Unknown option: {}", flag)),
+        _ => options.files.push(PathBuf::from(arg)),
+      }
+    }
+    if options.cols < 2 || options.rows < 1 || options.chunk == 0 || options.iterations == 0 {
+      return Err("--cols, --rows, --chunk and --iterations must be positive".to_string());
+    }
+    Ok(Some(options))
+  }
+}
+
+/// 单个负载的结果
+#[derive(Serialize)]
+struct BenchResult {
+  workload: String,
+  bytes: u64,
+  frames: usize,
+  total_ms: f64,
+  mib_per_sec: f64,
+  /// 各阶段耗时：解码与解析、生成快照、网格布局
+  parse_ms: f64,
+  snapshot_ms: f64,
+  layout_ms: f64,
+  /// 每帧延迟百分位（微秒）
+  p50_us: f64,
+  p95_us: f64,
+  p99_us: f64,
+  max_us: f64,
+}
+
+/// 运行基准测试，返回进程退出码
+pub fn run(args: impl Iterator<Item = String>) -> i32 {
+  let options = match Options::parse(args) {
+    Ok(Some(options)) => options,
+    Ok(None) => {
+      println!("{}", USAGE);
+      return 0;
+    }
+    Err(e) => {
+      eprintln!("{}\n\n{}", e, USAGE);
+      return 2;
+    }
+  };
+
+  let workloads: Vec<(String, Vec<u8>)> = if options.files.is_empty() {
+    synthetic_workloads(options.rows)
+  } else {
+    let mut workloads = Vec::new();
+    for path in &options.files {
+      match std::fs::read(path) {
+        Ok(data) => workloads.push((path.display().to_string(), data)),
+        Err(e) => {
+          eprintln!("Failed to read {}: {}", path.display(), e);
+          return 1;
+        }
+      }
+    }
+    workloads
+  };
+
+  let results: Vec<BenchResult> = workloads
+    .iter()
+    .map(|(name, data)| bench(name, data, &options))
+    .collect();
+
+  if options.json {
+    match serde_json::to_string_pretty(&results) {
+      Ok(json) => println!("{}", json),
+      Err(e) => {
+        eprintln!("Failed to serialize results: {}", e);
+        return 1;
+      }
+    }
+  } else {
+    print!("{}", format_table(&results, &options));
+  }
+  0
+}
+
+/// 按块重放数据，每块对应一帧：解码、解析、生成快照并布局
+fn bench(name: &str, data: &[u8], options: &Options) -> BenchResult {
+  let size = TerminalSize {
+    rows: options.rows,
+    cols: options.cols,
+    pixel_width: 0,
+    pixel_height: 0,
+  };
+  let rules = HighlightRule::defaults();
+  let mut frame_times = Vec::new();
+  let mut parse = Duration::ZERO;
+  let mut snapshot = Duration::ZERO;
+  let mut layout = Duration::ZERO;
+
+  for _ in 0..options.iterations {
+    let config = Config {
+      scrolling_history: DEFAULT_SCROLL_HISTORY_LINES,
+      ..Config::default()
+    };
+    let mut term = Term::new(config, &TermDimensions::from(size), VoidListener);
+    let mut parser = Processor::<StdSyncHandler>::new();
+    let mut decoder = OutputDecoder::new(TerminalEncoding::default());
+    let mut highlights = HighlightScanner::new(&rules);
+
+    for chunk in data.chunks(options.chunk) {
+      let start = Instant::now();
+      let text = decoder.decode(chunk);
+      parser.advance(&mut term, text.as_bytes());
+      highlights.refresh(&term, false);
+      let parsed = Instant::now();
+      let content = Terminal::build_content(&term, &highlights, decoder.status(), None, false);
+      let snapshotted = Instant::now();
+      let runs = TerminalElement::layout_grid(&content);
+      std::hint::black_box(runs);
+      let laid_out = Instant::now();
+
+      parse += parsed - start;
+      snapshot += snapshotted - parsed;
+      layout += laid_out - snapshotted;
+      frame_times.push(laid_out - start);
+    }
+  }
+
+  let total = parse + snapshot + layout;
+  let bytes = data.len() as u64 * options.iterations as u64;
+  frame_times.sort_unstable();
+  let percentile = |p: f64| {
+    let index = ((frame_times.len() as f64 * p).ceil() as usize).saturating_sub(1);
+    frame_times
+      .get(index)
+      .map_or(0.0, |time| time.as_secs_f64() * 1e6)
+  };
+
+  BenchResult {
+    workload: name.to_string(),
+    bytes,
+    frames: frame_times.len(),
+    total_ms: total.as_secs_f64() * 1e3,
+    mib_per_sec: bytes as f64 / (1024.0 * 1024.0) / total.as_secs_f64().max(f64::EPSILON),
+    parse_ms: parse.as_secs_f64() * 1e3,
+    snapshot_ms: snapshot.as_secs_f64() * 1e3,
+    layout_ms: layout.as_secs_f64() * 1e3,
+    p50_us: percentile(0.50),
+    p95_us: percentile(0.95),
+    p99_us: percentile(0.99),
+    max_us: percentile(1.0),
+  }
+}
+
+fn format_table(results: &[BenchResult], options: &Options) -> String {
+  let mut out = String::new();
+  let _ = writeln!(
+    out,
+    "catus {} · {}x{} grid · {} B/frame · {} iteration(s)\n",
+    env!("CARGO_PKG_VERSION"),
+    options.cols,
+    options.rows,
+    options.chunk,
+    options.iterations
+  );
+  let _ = writeln!(
+    out,
+    "{:<24} {:>10} {:>8} {:>10} {:>8} {:>8} {:>8} {:>8}",
+    "workload", "MiB/s", "frames", "total ms", "p50 µs", "p95 µs", "p99 µs", "max µs"
+  );
+  for result in results {
+    let _ = writeln!(
+      out,
+      "{:<24} {:>10.1} {:>8} {:>10.1} {:>8.0} {:>8.0} {:>8.0} {:>8.0}",
+      result.workload,
+      result.mib_per_sec,
+      result.frames,
+      result.total_ms,
+      result.p50_us,
+      result.p95_us,
+      result.p99_us,
+      result.max_us
+    );
+    let _ = writeln!(
+      out,
+      "{:<24} parse {:.1} ms · snapshot {:.1} ms · layout {:.1} ms",
+      "", result.parse_ms, result.snapshot_ms, result.layout_ms
+    );
+  }
+  out
+}
+
+/// 内置的合成负载：大量彩色日志、CJK 宽字符和全屏应用的重绘
+fn synthetic_workloads(rows: u16) -> Vec<(String, Vec<u8>)> {
+  let mut log = Vec::with_capacity(SYNTHETIC_SIZE);
+  let levels = [
+    ("32", "INFO"),
+    ("33", "WARN"),
+    ("31", "ERROR"),
+    ("36", "DEBUG"),
+  ];
+  for i in 0.. {
+    if log.len() >= SYNTHETIC_SIZE {
+      break;
+    }
+    let (color, level) = levels[i % levels.len()];
+    let _ = write!(
+      log,
+      "\x1b[2m2024-01-01 12:{:02}:{:02}\x1b[0m \x1b[1;{}m{:<5}\x1b[0m worker-{} handled request /api/v1/items/{} in {} ms\r\n",
+      i / 60 % 60,
+      i % 60,
+      color,
+      level,
+      i % 8,
+      i * 7919 % 100_000,
+      i % 250
+    );
+  }
+
+  let mut cjk = Vec::with_capacity(SYNTHETIC_SIZE);
+  let phrases = [
+    "终端渲染性能测试",
+    "日本語のテキスト",
+    "한국어 텍스트",
+    "emoji ✓ → ★",
+  ];
+  for i in 0.. {
+    if cjk.len() >= SYNTHETIC_SIZE {
+      break;
+    }
+    let _ = write!(
+      cjk,
+      "{} {} {}\r\n",
+      i,
+      phrases[i % phrases.len()],
+      phrases[(i + 1) % phrases.len()]
+    );
+  }
+
+  // 进入备用屏幕，逐帧定位光标重绘整屏，带背景色
+  let mut fullscreen = Vec::with_capacity(SYNTHETIC_SIZE);
+  fullscreen.extend_from_slice(b"\x1b[?1049h");
+  for frame in 0.. {
+    if fullscreen.len() >= SYNTHETIC_SIZE {
+      break;
+    }
+    let _ = write!(fullscreen, "\x1b[H");
+    for row in 1..=rows as usize {
+      let _ = write!(
+        fullscreen,
+        "\x1b[{};1H\x1b[48;5;{}m\x1b[38;5;{}m{:>5} {:<60}\x1b[0m\x1b[K",
+        row,
+        (frame + row) % 256,
+        (frame * 3 + row) % 256,
+        frame * rows as usize + row,
+        "process  cpu% mem% command"
+      );
+    }
+  }
+  fullscreen.extend_from_slice(b"\x1b[?1049l");
+
+  vec![
+    ("synthetic/colored-log".to_string(), log),
+    ("synthetic/cjk".to_string(), cjk),
+    ("synthetic/fullscreen".to_string(), fullscreen),
+  ]
+}
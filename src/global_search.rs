@@ -0,0 +1,219 @@
+use std::rc::Rc;
+use std::time::Duration;
+
+use gpui::*;
+use gpui_component::input::{Input, InputEvent, InputState};
+use gpui_component::{ActiveTheme as _, h_flex, v_flex};
+
+use crate::terminal::search::{MAX_MATCHES, SearchMatch};
+use crate::terminal::{Terminal, TerminalView};
+use crate::workspace::{TabId, Workspace};
+
+/// 输入停止多久后开始搜索
+const SEARCH_DEBOUNCE: Duration = Duration::from_millis(150);
+
+actions!(
+  workspace,
+  [
+    /// 在所有打开的终端中搜索
+    FindInTerminals
+  ]
+);
+
+/// 注册快捷键
+pub fn init(cx: &mut App) {
+  #[cfg(target_os = "macos")]
+  cx.bind_keys([KeyBinding::new("cmd-shift-f", FindInTerminals, None)]);
+  #[cfg(not(target_os = "macos"))]
+  cx.bind_keys([KeyBinding::new("ctrl-shift-f", FindInTerminals, None)]);
+}
+
+/// 选中匹配时的回调：Tab、终端和匹配所在行
+pub type OnSelect = Rc<dyn Fn(TabId, Entity<Terminal>, usize, &mut Window, &mut App)>;
+
+/// 一个 Tab 中的匹配
+struct TabMatches {
+  tab_id: TabId,
+  title: SharedString,
+  terminal: Entity<Terminal>,
+  matches: Vec<SearchMatch>,
+}
+
+/// 跨终端搜索：搜索所有终端 Tab 的滚动历史，结果按 Tab 分组
+///
+/// 私密终端不参与搜索，与禁止复制一致。
+pub struct GlobalSearch {
+  workspace: Entity<Workspace>,
+  input: Entity<InputState>,
+  results: Vec<TabMatches>,
+  /// 已搜索的查询，输入为空时为 None
+  query: Option<String>,
+  on_select: OnSelect,
+  _search: Task<()>,
+  _subscription: Subscription,
+}
+
+impl GlobalSearch {
+  pub fn new(
+    workspace: Entity<Workspace>,
+    on_select: OnSelect,
+    window: &mut Window,
+    cx: &mut Context<Self>,
+  ) -> Self {
+    let input = cx.new(|cx| InputState::new(window, cx).placeholder("Search all terminals"));
+    input.update(cx, |input, cx| input.focus(window, cx));
+    let subscription = cx.subscribe_in(&input, window, |this, _, event, window, cx| match event {
+      InputEvent::Change => this.search(cx),
+      InputEvent::PressEnter { .. } => this.select_first(window, cx),
+      _ => {}
+    });
+
+    Self {
+      workspace,
+      input,
+      results: Vec::new(),
+      query: None,
+      on_select,
+      _search: Task::ready(()),
+      _subscription: subscription,
+    }
+  }
+
+  /// 输入停止后依次在各个终端的后台任务中搜索
+  fn search(&mut self, cx: &mut Context<Self>) {
+    let query = self.input.read(cx).value().trim().to_string();
+    if query.is_empty() {
+      self.results.clear();
+      self.query = None;
+      self._search = Task::ready(());
+      cx.notify();
+      return;
+    }
+
+    let terminals: Vec<_> = self
+      .workspace
+      .read(cx)
+      .tabs
+      .iter()
+      .filter_map(|tab| {
+        let view = tab.downcast::<TerminalView>()?;
+        let terminal = view.read(cx).terminal().clone();
+        if terminal.read(cx).options().no_scrollback {
+          return None;
+        }
+        Some((tab.id, tab.state.read(cx).title.clone(), terminal))
+      })
+      .collect();
+
+    self._search = cx.spawn(async move |this, cx| {
+      cx.background_executor().timer(SEARCH_DEBOUNCE).await;
+      let mut results = Vec::new();
+      for (tab_id, title, terminal) in terminals {
+        let Ok(matches) = terminal.read_with(cx, |terminal, _| terminal.search(&query)) else {
+          continue;
+        };
+        let matches = matches.await;
+        if !matches.is_empty() {
+          results.push(TabMatches {
+            tab_id,
+            title,
+            terminal,
+            matches,
+          });
+        }
+      }
+      let _ = this.update(cx, |this, cx| {
+        this.results = results;
+        this.query = Some(query);
+        cx.notify();
+      });
+    });
+  }
+
+  /// 回车选中第一个匹配
+  fn select_first(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+    let Some(group) = self.results.first() else {
+      return;
+    };
+    let Some(found) = group.matches.first() else {
+      return;
+    };
+    let (tab_id, terminal, row) = (group.tab_id, group.terminal.clone(), found.row);
+    (self.on_select)(tab_id, terminal, row, window, cx);
+  }
+
+  fn render_status(&self, cx: &App) -> Option<impl IntoElement> {
+    let message = match &self.query {
+      None => "Type to search the scrollback of all open terminals".to_string(),
+      Some(query) if self.results.is_empty() => format!("No matches for \"{}\"", query),
+      Some(_) => return None,
+    };
+    Some(
+      div()
+        .py_2()
+        .text_sm()
+        .text_color(cx.theme().muted_foreground)
+        .child(message),
+    )
+  }
+}
+
+impl Render for GlobalSearch {
+  fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    let status = self.render_status(cx);
+    let groups = self.results.iter().enumerate().map(|(group_index, group)| {
+      let count = if group.matches.len() >= MAX_MATCHES {
+        format!("{}+ lines", MAX_MATCHES)
+      } else if group.matches.len() == 1 {
+        "1 line".to_string()
+      } else {
+        format!("{} lines", group.matches.len())
+      };
+      let rows = group.matches.iter().enumerate().map(|(index, found)| {
+        let (tab_id, terminal, row) = (group.tab_id, group.terminal.clone(), found.row);
+        let on_select = self.on_select.clone();
+        div()
+          .id(("global-search-match", group_index * MAX_MATCHES + index))
+          .px_2()
+          .py_1()
+          .rounded_sm()
+          .truncate()
+          .font_family("monospace")
+          .text_xs()
+          .cursor_pointer()
+          .hover(|style| style.bg(cx.theme().secondary_hover))
+          .child(found.text.clone())
+          .on_click(move |_, window, cx| on_select(tab_id, terminal.clone(), row, window, cx))
+      });
+      v_flex()
+        .gap_px()
+        .child(
+          h_flex()
+            .gap_2()
+            .pt_2()
+            .pb_1()
+            .text_sm()
+            .child(div().flex_1().truncate().child(group.title.clone()))
+            .child(
+              div()
+                .text_xs()
+                .text_color(cx.theme().muted_foreground)
+                .child(count),
+            ),
+        )
+        .children(rows)
+    });
+
+    v_flex()
+      .gap_2()
+      .child(Input::new(&self.input))
+      .children(status)
+      .child(
+        v_flex()
+          .id("global-search-results")
+          .max_h(px(420.))
+          .overflow_y_scroll()
+          .children(groups),
+      )
+  }
+}
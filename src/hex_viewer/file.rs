@@ -0,0 +1,252 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// 每行显示的字节数
+pub const BYTES_PER_ROW: usize = 16;
+/// 按页读取文件，只缓存最近访问的页，避免把整个文件读入内存；页大小是行宽的整数倍，一行不会跨页
+const PAGE_SIZE: u64 = 64 * 1024;
+const MAX_CACHED_PAGES: usize = 16;
+/// 搜索时每次读取的块大小
+const SEARCH_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// 以只读方式按需分页读取的文件
+///
+/// 页由调用方在后台线程用 `load_page` 读取后放入缓存，界面线程只读取已缓存的页
+pub struct HexFile {
+  path: PathBuf,
+  len: u64,
+  pages: HashMap<u64, Arc<Vec<u8>>>,
+  /// 页的访问顺序，最近访问的在末尾
+  recent: VecDeque<u64>,
+}
+
+impl HexFile {
+  pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+    let path = path.into();
+    let len = File::open(&path)?.metadata()?.len();
+    Ok(Self {
+      path,
+      len,
+      pages: HashMap::new(),
+      recent: VecDeque::new(),
+    })
+  }
+
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  pub fn len(&self) -> u64 {
+    self.len
+  }
+
+  /// 行数（最后一行可能不满）
+  pub fn rows(&self) -> usize {
+    self.len.div_ceil(BYTES_PER_ROW as u64) as usize
+  }
+
+  /// 某一行所在的页
+  pub fn page_of_row(row: usize) -> u64 {
+    (row * BYTES_PER_ROW) as u64 / PAGE_SIZE
+  }
+
+  /// 已缓存时返回某一行的字节，页未读取时返回 None
+  pub fn cached_row(&mut self, row: usize) -> Option<Vec<u8>> {
+    let offset = (row * BYTES_PER_ROW) as u64;
+    let index = offset / PAGE_SIZE;
+    let page = self.pages.get(&index)?.clone();
+    self.recent.retain(|i| *i != index);
+    self.recent.push_back(index);
+
+    let start = ((offset - index * PAGE_SIZE) as usize).min(page.len());
+    let stop = (start + BYTES_PER_ROW).min(page.len());
+    Some(page[start..stop].to_vec())
+  }
+
+  /// 放入后台读取的页，超出数量时淘汰最久未访问的页
+  pub fn insert_page(&mut self, index: u64, page: Vec<u8>) {
+    if self.pages.contains_key(&index) {
+      return;
+    }
+    if self.recent.len() >= MAX_CACHED_PAGES
+      && let Some(evicted) = self.recent.pop_front()
+    {
+      self.pages.remove(&evicted);
+    }
+    self.pages.insert(index, Arc::new(page));
+    self.recent.push_back(index);
+  }
+}
+
+/// 读取一页，使用独立的文件句柄，可以在后台线程中运行
+pub fn load_page(path: &Path, index: u64) -> io::Result<Vec<u8>> {
+  let mut file = File::open(path)?;
+  let mut buf = Vec::with_capacity(PAGE_SIZE as usize);
+  file.seek(SeekFrom::Start(index * PAGE_SIZE))?;
+  file.take(PAGE_SIZE).read_to_end(&mut buf)?;
+  Ok(buf)
+}
+
+/// 从 `from` 开始向后搜索 `needle`，到达文件末尾后从头继续
+///
+/// 使用独立的文件句柄分块读取，可以在后台线程中运行
+pub fn find(path: &Path, needle: &[u8], from: u64) -> io::Result<Option<u64>> {
+  if needle.is_empty() {
+    return Ok(None);
+  }
+  let mut file = File::open(path)?;
+  let len = file.metadata()?.len();
+  let from = from.min(len);
+
+  if let Some(found) = find_in_range(&mut file, needle, from, len)? {
+    return Ok(Some(found));
+  }
+  // 回绕：匹配可能跨越 from，因此多读 needle.len() - 1 个字节
+  let end = (from + needle.len() as u64 - 1).min(len);
+  find_in_range(&mut file, needle, 0, end)
+}
+
+fn find_in_range(file: &mut File, needle: &[u8], start: u64, end: u64) -> io::Result<Option<u64>> {
+  let mut buf = Vec::with_capacity(SEARCH_CHUNK_SIZE + needle.len());
+  let mut pos = start;
+  file.seek(SeekFrom::Start(start))?;
+  while pos < end {
+    // 保留上一块末尾的 needle.len() - 1 个字节，以便找到跨块的匹配
+    let keep = buf.len().min(needle.len() - 1);
+    buf.drain(..buf.len() - keep);
+    let buf_start = pos - keep as u64;
+
+    let chunk = SEARCH_CHUNK_SIZE.min((end - pos) as usize);
+    let read = (&mut *file).take(chunk as u64).read_to_end(&mut buf)?;
+    if read == 0 {
+      break;
+    }
+    pos += read as u64;
+
+    if let Some(index) = buf
+      .windows(needle.len())
+      .position(|window| window == needle)
+    {
+      return Ok(Some(buf_start + index as u64));
+    }
+  }
+  Ok(None)
+}
+
+/// 解析搜索内容：`0x` 开头按十六进制字节解析（可用空格分隔），否则按 UTF-8 文本搜索
+pub fn parse_pattern(query: &str) -> Result<Vec<u8>, String> {
+  let Some(hex) = query
+    .trim()
+    .strip_prefix("0x")
+    .or_else(|| query.trim().strip_prefix("0X"))
+  else {
+    return Ok(query.as_bytes().to_vec());
+  };
+
+  let digits: String = hex.chars().filter(|c| !c.is_whitespace()).collect();
+  if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+    return Err(format!("Invalid hex pattern: {}", hex.trim()));
+  }
+  if digits.is_empty() || !digits.len().is_multiple_of(2) {
+    return Err("Hex pattern must have an even number of digits".to_string());
+  }
+  Ok(
+    (0..digits.len())
+      .step_by(2)
+      .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).unwrap_or_default())
+      .collect(),
+  )
+}
+
+/// 解析跳转偏移：支持十进制和 `0x` 开头的十六进制
+pub fn parse_offset(text: &str) -> Result<u64, String> {
+  let text = text.trim().replace('_', "");
+  let parsed = match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+    Some(hex) => u64::from_str_radix(hex, 16),
+    None => text.parse(),
+  };
+  parsed.map_err(|_| format!("Invalid offset: {}", text))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// 在临时目录中写入测试文件，每个测试使用不同的文件名
+  fn temp_file(name: &str, contents: &[u8]) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("catus-hex-{}-{}", std::process::id(), name));
+    std::fs::write(&path, contents).unwrap();
+    path
+  }
+
+  #[test]
+  fn finds_match_across_chunk_boundary() {
+    let mut contents = vec![0u8; SEARCH_CHUNK_SIZE * 2];
+    let at = SEARCH_CHUNK_SIZE - 2;
+    contents[at..at + 4].copy_from_slice(b"WXYZ");
+    let path = temp_file("chunk", &contents);
+    assert_eq!(find(&path, b"WXYZ", 0).unwrap(), Some(at as u64));
+  }
+
+  #[test]
+  fn wraps_around_to_start() {
+    let path = temp_file("wrap", b"needle....haystack");
+    assert_eq!(find(&path, b"needle", 1).unwrap(), Some(0));
+    // 跨越 from 的匹配在回绕后找到
+    assert_eq!(find(&path, b"hay", 12).unwrap(), Some(10));
+    assert_eq!(find(&path, b"missing", 5).unwrap(), None);
+  }
+
+  #[test]
+  fn finds_match_at_final_byte() {
+    let path = temp_file("last", b"aaaaaaab");
+    assert_eq!(find(&path, b"b", 0).unwrap(), Some(7));
+    assert_eq!(find(&path, b"ab", 7).unwrap(), Some(6));
+    // from 超出文件长度时从头搜索
+    assert_eq!(find(&path, b"b", 100).unwrap(), Some(7));
+  }
+
+  #[test]
+  fn empty_needle_finds_nothing() {
+    let path = temp_file("empty", b"abc");
+    assert_eq!(find(&path, b"", 0).unwrap(), None);
+  }
+
+  #[test]
+  fn parses_patterns() {
+    assert_eq!(parse_pattern("abc").unwrap(), b"abc");
+    assert_eq!(
+      parse_pattern("0xDEADbeef").unwrap(),
+      [0xde, 0xad, 0xbe, 0xef]
+    );
+    assert_eq!(parse_pattern(" 0X 0a 0B ").unwrap(), [0x0a, 0x0b]);
+  }
+
+  #[test]
+  fn rejects_malformed_hex_patterns() {
+    assert!(parse_pattern("0xzz").is_err());
+    assert!(parse_pattern("0xabc").is_err());
+    assert!(parse_pattern("0x").is_err());
+    assert!(parse_pattern("0x 1g").is_err());
+  }
+
+  #[test]
+  fn parses_offsets() {
+    assert_eq!(parse_offset("1024").unwrap(), 1024);
+    assert_eq!(parse_offset(" 0x1F ").unwrap(), 0x1f);
+    assert_eq!(parse_offset("0X10").unwrap(), 16);
+    assert_eq!(parse_offset("1_000").unwrap(), 1000);
+  }
+
+  #[test]
+  fn rejects_malformed_offsets() {
+    assert!(parse_offset("").is_err());
+    assert!(parse_offset("0x").is_err());
+    assert!(parse_offset("0xfg").is_err());
+    assert!(parse_offset("-1").is_err());
+    assert!(parse_offset("12ab").is_err());
+  }
+}
@@ -0,0 +1,7 @@
+pub mod file;
+pub mod view;
+
+/// 注册十六进制查看器 Tab 类型
+pub fn init(cx: &mut gpui::App) {
+  crate::tab_kind::TabKinds::register(&view::HEX_VIEWER_TAB_KIND, cx);
+}
@@ -0,0 +1,366 @@
+use std::collections::HashSet;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use gpui::*;
+use gpui_component::button::Button;
+use gpui_component::input::{Input, InputEvent, InputState};
+use gpui_component::{ActiveTheme as _, IconName, Sizable as _, h_flex, v_flex};
+use serde_json::{Value, json};
+
+use crate::hex_viewer::file::{self, BYTES_PER_ROW, HexFile};
+use crate::tab_kind::{self, TabKindDescriptor};
+
+/// 十六进制查看器 Tab 类型，状态为 `{"path": ...}`
+pub static HEX_VIEWER_TAB_KIND: TabKindDescriptor = TabKindDescriptor {
+  id: "hex_viewer",
+  icon: IconName::File,
+  menu_label: Some("Hex Viewer…"),
+  prompt: Some(prompt_for_file),
+  build: build_hex_viewer_tab,
+  title: |view, cx| {
+    let Ok(view) = view.clone().downcast::<HexView>() else {
+      return "Hex Viewer".into();
+    };
+    let path = view.read(cx).path();
+    path
+      .file_name()
+      .map(|name| name.to_string_lossy().into_owned())
+      .unwrap_or_else(|| path.display().to_string())
+      .into()
+  },
+  detail: |view, cx| {
+    let view = view.clone().downcast::<HexView>().ok()?;
+    Some(view.read(cx).path().display().to_string().into())
+  },
+  focus_handle: tab_kind::no_focus,
+  serialize: |view, cx| match view.clone().downcast::<HexView>() {
+    Ok(view) => path_state(&view.read(cx).path()),
+    Err(_) => Value::Null,
+  },
+};
+
+/// 选择要打开的文件
+fn prompt_for_file(_window: &mut Window, cx: &mut App) -> Task<Option<Value>> {
+  let paths = cx.prompt_for_paths(PathPromptOptions {
+    files: true,
+    directories: false,
+    multiple: false,
+    prompt: Some("Open".into()),
+  });
+  cx.foreground_executor().spawn(async move {
+    let path = paths.await.ok()?.ok()??.into_iter().next()?;
+    Some(path_state(&path))
+  })
+}
+
+/// 非 UTF-8 路径无法写入 JSON，此时返回 Null
+fn path_state(path: &Path) -> Value {
+  match path.to_str() {
+    Some(path) => json!({ "path": path }),
+    None => Value::Null,
+  }
+}
+
+fn build_hex_viewer_tab(
+  state: Option<&Value>,
+  window: &mut Window,
+  cx: &mut App,
+) -> Result<AnyView, String> {
+  let path = state
+    .and_then(|state| state.get("path"))
+    .and_then(Value::as_str)
+    .map(PathBuf::from)
+    .ok_or("Hex viewer needs a file path")?;
+  let file =
+    HexFile::open(&path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+  Ok(cx.new(|cx| HexView::new(file, window, cx)).into())
+}
+
+/// 十六进制查看器
+///
+/// 只渲染可见行（uniform_list），文件内容按页在后台读取并缓存，读取完成前显示占位行
+pub struct HexView {
+  file: HexFile,
+  /// 正在读取的页，不重复请求
+  loading: HashSet<u64>,
+  /// 读取失败的页，点击重试前不再请求
+  failed: HashSet<u64>,
+  scroll_handle: UniformListScrollHandle,
+  goto_input: Entity<InputState>,
+  search_input: Entity<InputState>,
+  /// 当前高亮的字节范围（跳转位置或搜索匹配）
+  highlight: Option<Range<u64>>,
+  /// 状态栏信息（错误、未找到等）
+  status: Option<SharedString>,
+  search_task: Option<Task<()>>,
+  _subscriptions: Vec<Subscription>,
+}
+
+impl HexView {
+  pub fn new(file: HexFile, window: &mut Window, cx: &mut Context<Self>) -> Self {
+    let goto_input = cx.new(|cx| InputState::new(window, cx).placeholder("Go to offset (0x…)"));
+    let search_input =
+      cx.new(|cx| InputState::new(window, cx).placeholder("Search text or hex (0x…)"));
+
+    let subscriptions = vec![
+      cx.subscribe_in(&goto_input, window, |this, _, event, _window, cx| {
+        if let InputEvent::PressEnter { .. } = event {
+          this.goto(cx);
+        }
+      }),
+      cx.subscribe_in(&search_input, window, |this, _, event, _window, cx| {
+        if let InputEvent::PressEnter { .. } = event {
+          this.search(cx);
+        }
+      }),
+    ];
+
+    Self {
+      file,
+      loading: HashSet::new(),
+      failed: HashSet::new(),
+      scroll_handle: UniformListScrollHandle::new(),
+      goto_input,
+      search_input,
+      highlight: None,
+      status: None,
+      search_task: None,
+      _subscriptions: subscriptions,
+    }
+  }
+
+  pub fn path(&self) -> PathBuf {
+    self.file.path().to_path_buf()
+  }
+
+  fn reveal(&mut self, range: Range<u64>, cx: &mut Context<Self>) {
+    let row = (range.start / BYTES_PER_ROW as u64) as usize;
+    self
+      .scroll_handle
+      .scroll_to_item(row, ScrollStrategy::Center);
+    self.highlight = Some(range);
+    cx.notify();
+  }
+
+  fn goto(&mut self, cx: &mut Context<Self>) {
+    let text = self.goto_input.read(cx).value();
+    match file::parse_offset(&text) {
+      Ok(offset) if offset < self.file.len() => {
+        self.status = None;
+        self.reveal(offset..offset + 1, cx);
+      }
+      Ok(offset) => {
+        self.status = Some(format!("Offset {:#x} is past the end of the file", offset).into());
+        cx.notify();
+      }
+      Err(e) => {
+        self.status = Some(e.into());
+        cx.notify();
+      }
+    }
+  }
+
+  /// 在后台线程中从当前高亮之后开始搜索，重复按回车可以查找下一个
+  fn search(&mut self, cx: &mut Context<Self>) {
+    let query = self.search_input.read(cx).value();
+    let needle = match file::parse_pattern(&query) {
+      Ok(needle) if !needle.is_empty() => needle,
+      Ok(_) => return,
+      Err(e) => {
+        self.status = Some(e.into());
+        cx.notify();
+        return;
+      }
+    };
+
+    let from = self.highlight.as_ref().map_or(0, |range| range.start + 1);
+    let path = self.path();
+    self.status = Some("Searching…".into());
+    cx.notify();
+
+    let task = cx.background_spawn({
+      let needle = needle.clone();
+      async move { file::find(&path, &needle, from) }
+    });
+    self.search_task = Some(cx.spawn(async move |this, cx| {
+      let result = task.await;
+      let _ = this.update(cx, |this, cx| {
+        this.search_task = None;
+        match result {
+          Ok(Some(offset)) => {
+            this.status = None;
+            this.reveal(offset..offset + needle.len() as u64, cx);
+          }
+          Ok(None) => {
+            this.status = Some("No matches".into());
+            cx.notify();
+          }
+          Err(e) => {
+            this.status = Some(format!("Search failed: {}", e).into());
+            cx.notify();
+          }
+        }
+      });
+    }));
+  }
+
+  /// 在后台读取一页，完成后放入缓存并重新渲染
+  fn load_page(&mut self, index: u64, cx: &mut Context<Self>) {
+    if self.failed.contains(&index) || !self.loading.insert(index) {
+      return;
+    }
+    let path = self.path();
+    let task = cx.background_spawn(async move { file::load_page(&path, index) });
+    cx.spawn(async move |this, cx| {
+      let result = task.await;
+      let _ = this.update(cx, |this, cx| {
+        this.loading.remove(&index);
+        match result {
+          Ok(page) => this.file.insert_page(index, page),
+          Err(e) => {
+            this.failed.insert(index);
+            eprintln!("Failed to read {}: {}", this.file.path().display(), e);
+            this.status = Some(format!("Read failed: {}", e).into());
+          }
+        }
+        cx.notify();
+      });
+    })
+    .detach();
+  }
+
+  /// 重新读取失败的页
+  fn retry_failed(&mut self, cx: &mut Context<Self>) {
+    self.failed.clear();
+    self.status = None;
+    cx.notify();
+  }
+
+  fn render_rows(
+    &mut self,
+    range: Range<usize>,
+    _window: &mut Window,
+    cx: &mut Context<Self>,
+  ) -> Vec<AnyElement> {
+    let highlight_style = HighlightStyle {
+      background_color: Some(cx.theme().selection),
+      ..Default::default()
+    };
+
+    range
+      .map(|row_index| {
+        let offset = (row_index * BYTES_PER_ROW) as u64;
+        let Some(row) = self.file.cached_row(row_index) else {
+          let page = HexFile::page_of_row(row_index);
+          self.load_page(page, cx);
+          let placeholder = if self.failed.contains(&page) {
+            "read failed"
+          } else {
+            "…"
+          };
+          return h_flex()
+            .gap_4()
+            .text_color(cx.theme().muted_foreground)
+            .child(format!("{:08x}", offset))
+            .child(placeholder)
+            .into_any_element();
+        };
+        // 不满一行时补齐空格，使 ASCII 列保持对齐
+        let hex: String = row.iter().map(|byte| format!("{:02x} ", byte)).collect();
+        let hex = format!("{:<width$}", hex, width = BYTES_PER_ROW * 3);
+        let ascii: String = row
+          .iter()
+          .map(|&byte| {
+            if byte.is_ascii_graphic() || byte == b' ' {
+              byte as char
+            } else {
+              '.'
+            }
+          })
+          .collect();
+
+        // 高亮范围与本行的交集（行内字节下标）
+        let selected = self.highlight.as_ref().and_then(|highlight| {
+          let from = highlight.start.max(offset);
+          let to = highlight.end.min(offset + row.len() as u64);
+          (from < to).then(|| (from - offset) as usize..(to - offset) as usize)
+        });
+        let (hex_highlights, ascii_highlights) = match selected {
+          Some(bytes) => (
+            vec![(bytes.start * 3..bytes.end * 3 - 1, highlight_style)],
+            vec![(bytes, highlight_style)],
+          ),
+          None => (Vec::new(), Vec::new()),
+        };
+
+        h_flex()
+          .gap_4()
+          .child(
+            div()
+              .text_color(cx.theme().muted_foreground)
+              .child(format!("{:08x}", offset)),
+          )
+          .child(StyledText::new(hex).with_highlights(hex_highlights))
+          .child(StyledText::new(ascii).with_highlights(ascii_highlights))
+          .into_any_element()
+      })
+      .collect()
+  }
+}
+
+impl Render for HexView {
+  fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    let rows = self.file.rows();
+    let info = format!("{} · {} bytes", self.file.path().display(), self.file.len());
+
+    v_flex()
+      .size_full()
+      .child(
+        h_flex()
+          .gap_2()
+          .p_2()
+          .border_b_1()
+          .border_color(cx.theme().border)
+          .child(
+            div()
+              .flex_1()
+              .min_w_0()
+              .overflow_hidden()
+              .text_ellipsis()
+              .text_sm()
+              .child(info),
+          )
+          .children(self.status.clone().map(|status| {
+            div()
+              .text_sm()
+              .text_color(cx.theme().muted_foreground)
+              .child(status)
+          }))
+          .children((!self.failed.is_empty()).then(|| {
+            Button::new("retry-failed-pages")
+              .label("Retry")
+              .small()
+              .on_click(cx.listener(|this, _, _window, cx| this.retry_failed(cx)))
+          }))
+          .child(
+            div()
+              .w(px(160.))
+              .child(Input::new(&self.goto_input).small()),
+          )
+          .child(
+            div()
+              .w(px(220.))
+              .child(Input::new(&self.search_input).small()),
+          ),
+      )
+      .child(
+        uniform_list("hex-rows", rows, cx.processor(Self::render_rows))
+          .flex_1()
+          .px_2()
+          .font_family(cx.theme().mono_font_family.clone())
+          .text_sm()
+          .track_scroll(self.scroll_handle.clone()),
+      )
+  }
+}
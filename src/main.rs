@@ -2,23 +2,59 @@ use gpui::*;
 use gpui_component::Root;
 
 mod app;
+#[cfg(unix)]
+mod askpass;
+mod bench;
+mod global_search;
+mod hex_viewer;
 mod main_view;
+mod migration;
+mod persist;
+mod session;
+mod settings;
+mod sftp;
+mod stats;
+mod tab_kind;
 mod terminal;
 mod workspace;
 
 use app::App as CatusApp;
 use main_view::MainView;
+use session::Session;
 
 fn main() {
+  let mut args = std::env::args().skip(1);
+  match args.next().as_deref() {
+    Some(bench::FLAG) => std::process::exit(bench::run(args)),
+    Some(terminal::stderr::FLAG) => std::process::exit(terminal::stderr::run(args)),
+    #[cfg(unix)]
+    Some(askpass::FLAG) => std::process::exit(askpass::run(args)),
+    _ => {}
+  }
+
   let app = Application::new().with_assets(gpui_component_assets::Assets);
 
   app.run(move |cx| {
     // Initialize GPUI Component
     gpui_component::init(cx);
+    settings::init(cx);
+    global_search::init(cx);
+    #[cfg(unix)]
+    askpass::init(cx);
+    // Tab 类型按注册顺序出现在新建菜单中
+    terminal::init(cx);
+    sftp::init(cx);
+    stats::init(cx);
+    hex_viewer::init(cx);
 
-    // 创建 App，包含一个默认的 Workspace
-    let catus_app = cx.new(|cx| CatusApp::new(cx));
-    let workspace = catus_app.read(cx).workspace().clone();
+    // 会话文件无法读取时不自动保存，避免覆盖
+    let (session, overwrite_session) = match Session::load() {
+      Ok(session) => (session, true),
+      Err(e) => {
+        eprintln!("Failed to restore session: {}", e);
+        (None, false)
+      }
+    };
 
     cx.open_window(
       WindowOptions {
@@ -32,6 +68,11 @@ fn main() {
       |window, cx| {
         cx.activate(true);
 
+        // 创建 App，从保存的会话恢复 Workspace
+        let catus_app = cx.new(|cx| CatusApp::new(session.as_ref(), window, cx));
+        let workspace = catus_app.read(cx).workspace().clone();
+        session::start_autosave(workspace.clone(), overwrite_session, cx);
+
         let view = cx.new(|_| MainView::new(workspace));
         cx.new(|cx| Root::new(view, window, cx))
       },
@@ -1,25 +1,36 @@
-use std::collections::HashMap;
+use std::rc::Rc;
 
 use gpui::*;
 use gpui_component::WindowExt;
+use gpui_component::button::{Button, ButtonVariants as _};
 use gpui_component::notification::Notification;
+use gpui_component::popover::Popover;
+use gpui_component::tooltip::Tooltip;
 use gpui_component::{ActiveTheme as _, Icon, IconName, StyledExt as _, tab::*, *};
 
-use crate::terminal::TerminalView;
-use crate::workspace::{TabId, TabType, Workspace};
+use crate::global_search::{FindInTerminals, GlobalSearch};
+use crate::tab_kind::{TabKindDescriptor, TabKinds};
+use crate::terminal::view::{
+  RunSelectionInNewTab, TERMINAL_TAB_KIND, confirm_command, run_command, with_selection_text,
+};
+use crate::terminal::{Terminal, TerminalView};
+use crate::workspace::{TabId, Workspace};
 
 /// Main view
 pub struct MainView {
   pub workspace: Entity<Workspace>,
-  /// Cache terminal views by tab ID so they aren't recreated on every render
-  terminal_views: HashMap<TabId, Entity<TerminalView>>,
+  /// Tab 栏的横向滚动状态（Tab 过多时滚轮/触控板横向滚动）
+  tab_scroll_handle: ScrollHandle,
+  /// 最近一次获得焦点的 Tab，只在切换 Tab 时移动焦点，避免抢走对话框和覆盖层的焦点
+  focused_tab: Option<TabId>,
 }
 
 impl MainView {
   pub fn new(workspace: Entity<Workspace>) -> Self {
     Self {
       workspace,
-      terminal_views: HashMap::new(),
+      tab_scroll_handle: ScrollHandle::new(),
+      focused_tab: None,
     }
   }
 
@@ -28,8 +39,9 @@ impl MainView {
       let id = tab.id;
       if self
         .workspace
-        .update(cx, |workspace, _cx| workspace.activate_tab(id))
+        .update(cx, |workspace, cx| workspace.activate_tab(id, cx))
       {
+        self.tab_scroll_handle.scroll_to_item(index);
         cx.notify();
       }
     }
@@ -40,7 +52,7 @@ impl MainView {
       let id = tab.id;
       if self
         .workspace
-        .update(cx, |workspace, _cx| workspace.close_tab(id))
+        .update(cx, |workspace, cx| workspace.close_tab(id, cx))
       {
         cx.notify();
       }
@@ -48,15 +60,190 @@ impl MainView {
   }
 
   fn handle_add_terminal(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-    if let Err(error_msg) = self
+    self.add_tab(&TERMINAL_TAB_KIND, None, window, cx);
+  }
+
+  /// 新建指定类型的 Tab，类型需要时先询问初始状态（如选择文件）
+  fn handle_new_tab(
+    &mut self,
+    kind: &'static TabKindDescriptor,
+    window: &mut Window,
+    cx: &mut Context<Self>,
+  ) {
+    let Some(prompt) = kind.prompt else {
+      self.add_tab(kind, None, window, cx);
+      return;
+    };
+    let state = prompt(window, cx);
+    cx.spawn_in(window, async move |this, cx| {
+      let Some(state) = state.await else {
+        return;
+      };
+      let _ = this.update_in(cx, |this, window, cx| {
+        this.add_tab(kind, Some(&state), window, cx);
+      });
+    })
+    .detach();
+  }
+
+  /// 添加 Tab，失败时显示错误通知
+  pub fn add_tab(
+    &mut self,
+    kind: &'static TabKindDescriptor,
+    state: Option<&serde_json::Value>,
+    window: &mut Window,
+    cx: &mut Context<Self>,
+  ) -> Option<TabId> {
+    match self.workspace.update(cx, |workspace, cx| {
+      workspace.open_tab(kind, state, window, cx)
+    }) {
+      Ok(id) => {
+        self.reveal_active_tab(cx);
+        Some(id)
+      }
+      Err(error_msg) => {
+        window.push_notification(Notification::error(error_msg), cx);
+        None
+      }
+    }
+  }
+
+  /// 在新的终端 Tab 中执行当前终端选中的文本
+  fn run_selection_in_new_tab(
+    &mut self,
+    _: &RunSelectionInNewTab,
+    window: &mut Window,
+    cx: &mut Context<Self>,
+  ) {
+    let Some(terminal) = self
+      .workspace
+      .read(cx)
+      .active_tab()
+      .and_then(|tab| tab.downcast::<TerminalView>())
+    else {
+      return;
+    };
+    let terminal = terminal.read(cx).terminal().clone();
+
+    let view = cx.entity();
+    with_selection_text(&terminal, window, cx, move |text, window, cx| {
+      confirm_command(text, window, cx, move |text, window, cx| {
+        view.update(cx, |this, cx| {
+          let Some(id) = this.add_tab(&TERMINAL_TAB_KIND, None, window, cx) else {
+            return;
+          };
+          let terminal = this
+            .workspace
+            .read(cx)
+            .tabs
+            .iter()
+            .find(|tab| tab.id == id)
+            .and_then(|tab| tab.downcast::<TerminalView>());
+          if let Some(terminal) = terminal {
+            let terminal = terminal.read(cx).terminal().clone();
+            run_command(&terminal, &text, cx);
+          }
+        });
+      });
+    });
+  }
+
+  /// 打开跨终端搜索
+  fn find_in_terminals(
+    &mut self,
+    _: &FindInTerminals,
+    window: &mut Window,
+    cx: &mut Context<Self>,
+  ) {
+    let view = cx.entity();
+    let workspace = self.workspace.clone();
+    let search = cx.new(|cx| {
+      GlobalSearch::new(
+        workspace,
+        Rc::new(move |tab_id, terminal, row, window, cx| {
+          view.update(cx, |this, cx| {
+            this.reveal_search_match(tab_id, &terminal, row, window, cx)
+          });
+        }),
+        window,
+        cx,
+      )
+    });
+    window.open_dialog(cx, move |dialog, _, _| {
+      dialog
+        .title("Find in All Terminals")
+        .w(px(640.))
+        .child(search.clone())
+    });
+  }
+
+  /// 激活匹配所在的 Tab，滚动到匹配的行并高亮
+  fn reveal_search_match(
+    &mut self,
+    tab_id: TabId,
+    terminal: &Entity<Terminal>,
+    row: usize,
+    window: &mut Window,
+    cx: &mut Context<Self>,
+  ) {
+    window.close_dialog(cx);
+    // Tab 可能在搜索后被关闭
+    if !self
       .workspace
-      .update(cx, |workspace, cx| workspace.add_terminal_tab(cx))
+      .update(cx, |workspace, cx| workspace.activate_tab(tab_id, cx))
     {
-      // 显示错误通知
-      window.push_notification(Notification::error(error_msg), cx);
-    } else {
-      cx.notify();
+      return;
     }
+    self.reveal_active_tab(cx);
+    terminal.update(cx, |terminal, _| terminal.reveal_row(row));
+  }
+
+  /// 将 Tab 栏滚动到当前激活的 Tab
+  fn reveal_active_tab(&mut self, cx: &mut Context<Self>) {
+    if let Some(index) = self.workspace.read(cx).active_index() {
+      self.tab_scroll_handle.scroll_to_item(index);
+    }
+    cx.notify();
+  }
+
+  fn render_add_tab_button(&self, cx: &mut Context<Self>) -> impl IntoElement {
+    let view = cx.entity();
+
+    Popover::new("add-tab-picker")
+      .mouse_button(MouseButton::Middle)
+      .anchor(Corner::TopLeft)
+      .trigger(
+        Button::new("add-tab-btn")
+          .ghost()
+          .small()
+          .ml(px(4.))
+          .icon(IconName::Plus)
+          .tooltip("New Terminal")
+          .on_click(cx.listener(|this, _, window, cx| {
+            this.handle_add_terminal(window, cx);
+          })),
+      )
+      .content(move |_state, _window, cx| {
+        let popover = cx.entity();
+        v_flex().gap_1().children(
+          TabKinds::all(cx)
+            .into_iter()
+            .filter_map(|kind| Some((kind, kind.menu_label?)))
+            .map(|(kind, label)| {
+              let view = view.clone();
+              let popover = popover.clone();
+              Button::new(SharedString::from(format!("new-{}-tab", kind.id)))
+                .ghost()
+                .small()
+                .icon(kind.icon.clone())
+                .label(label)
+                .on_click(move |_, window, cx| {
+                  popover.update(cx, |state, cx| state.dismiss(window, cx));
+                  view.update(cx, |this, cx| this.handle_new_tab(kind, window, cx));
+                })
+            }),
+        )
+      })
   }
 
   fn render_title_bar(&self, cx: &mut Context<Self>) -> impl IntoElement {
@@ -90,11 +277,13 @@ impl MainView {
           .items_center()
           .h_full()
           .pl(left_padding)
-          .flex_shrink_0()
+          .flex_1()
+          .min_w_0()
           // This div acts as the drag handle for the window
           .child(
             div()
               .h_full()
+              .flex_shrink_0()
               .w(px(60.)) // Extra drag area width
               .window_control_area(WindowControlArea::Drag),
           )
@@ -102,52 +291,67 @@ impl MainView {
           .child(
             TabBar::new("tab-bar")
               .with_variant(TabVariant::Tab)
+              // 允许 Tab 栏收缩，溢出的 Tab 通过滚轮横向滚动（纵向滚轮与 Shift+滚轮都会映射为横向）
+              .flex_1()
+              .min_w_0()
+              .track_scroll(&self.tab_scroll_handle)
               .selected_index(active_index)
               .on_click(cx.listener(|this, ix: &usize, window, cx| {
                 this.handle_tab_click(*ix, window, cx);
               }))
+              // 双击 Tab 栏空白处新建终端
+              .last_empty_space(
+                div()
+                  .id("tab-bar-empty-space")
+                  .flex_1()
+                  .min_w(px(12.))
+                  .h_full()
+                  .on_click(cx.listener(|this, event: &ClickEvent, window, cx| {
+                    if event.click_count() == 2 {
+                      this.handle_add_terminal(window, cx);
+                    }
+                  })),
+              )
               .children(tabs.iter().enumerate().map(|(ix, tab)| {
                 let state = tab.state.read(cx);
 
                 let tab_icon = state.icon.clone();
                 let title = state.title.clone();
+                let tooltip = tab.tooltip(cx);
 
-                Tab::new().label(title).icon(tab_icon).suffix(
-                  div()
-                    .id("tab-close")
-                    .flex()
-                    .items_center()
-                    .justify_center()
-                    .w(px(16.))
-                    .h(px(16.))
-                    .rounded_full()
-                    .hover(|style| style.bg(cx.theme().secondary_hover))
-                    .on_click(cx.listener(move |this, _, window, cx| {
+                Tab::new()
+                  .label(title)
+                  .icon(tab_icon)
+                  .tooltip(move |window, cx| Tooltip::new(tooltip.clone()).build(window, cx))
+                  // 中键关闭 Tab
+                  .on_mouse_down(
+                    MouseButton::Middle,
+                    cx.listener(move |this, _, window, cx| {
                       cx.stop_propagation();
                       this.handle_tab_close(ix, window, cx);
-                    }))
-                    .child(Icon::new(IconName::Close).with_size(px(12.))),
-                )
+                    }),
+                  )
+                  .suffix(
+                    div()
+                      .id("tab-close")
+                      .flex()
+                      .items_center()
+                      .justify_center()
+                      .w(px(16.))
+                      .h(px(16.))
+                      .rounded_full()
+                      .hover(|style| style.bg(cx.theme().secondary_hover))
+                      .tooltip(|window, cx| Tooltip::new("Close Tab").build(window, cx))
+                      .on_click(cx.listener(move |this, _, window, cx| {
+                        cx.stop_propagation();
+                        this.handle_tab_close(ix, window, cx);
+                      }))
+                      .child(Icon::new(IconName::Close).with_size(px(12.))),
+                  )
               })),
           )
-          // Add tab button
-          .child(
-            div()
-              .id("add-tab-btn")
-              .flex()
-              .items_center()
-              .justify_center()
-              .w(px(28.))
-              .h(px(28.))
-              .ml(px(4.))
-              .rounded_md()
-              .cursor_pointer()
-              .hover(|style| style.bg(cx.theme().secondary_hover))
-              .on_click(cx.listener(|this, _, window, cx| {
-                this.handle_add_terminal(window, cx);
-              }))
-              .child(Icon::new(IconName::Plus).small()),
-          ),
+          // Add tab button: 左键新建终端，中键弹出类型选择
+          .child(self.render_add_tab_button(cx)),
       )
   }
 
@@ -159,36 +363,19 @@ impl MainView {
     let active_tab = self.workspace.read(cx).active_tab().cloned();
 
     if let Some(tab) = active_tab {
-      match &tab.tab_type {
-        TabType::Terminal(terminal) => {
-          // Reuse existing TerminalView or create one
-          let terminal_view = self
-            .terminal_views
-            .entry(tab.id)
-            .or_insert_with(|| cx.new(|cx| TerminalView::new(terminal.clone(), cx)))
-            .clone();
-
-          // Ensure the terminal view is focused so it receives key events
-          terminal_view.focus_handle(cx).focus(window);
-
-          div()
-            .flex_1()
-            .size_full()
-            .child(terminal_view)
-            .into_any_element()
-        }
-        TabType::Sftp => {
-          // TODO: 实现 SFTP 视图
-          div()
-            .flex_1()
-            .size_full()
-            .flex()
-            .items_center()
-            .justify_center()
-            .child("SFTP view not implemented yet")
-            .into_any_element()
-        }
+      // Ensure the tab view is focused so it receives key events
+      if self.focused_tab != Some(tab.id)
+        && let Some(focus_handle) = (tab.kind.focus_handle)(&tab.view, cx)
+      {
+        focus_handle.focus(window);
+        self.focused_tab = Some(tab.id);
       }
+
+      div()
+        .flex_1()
+        .size_full()
+        .child(tab.view.clone())
+        .into_any_element()
     } else {
       // 没有激活的 Tab
       div()
@@ -205,14 +392,11 @@ impl MainView {
 
 impl Render for MainView {
   fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-    // Clean up terminal views for closed tabs
-    let tab_ids: std::collections::HashSet<TabId> =
-      self.workspace.read(cx).tabs.iter().map(|t| t.id).collect();
-    self.terminal_views.retain(|id, _| tab_ids.contains(id));
-
     div()
       .v_flex()
       .size_full()
+      .on_action(cx.listener(Self::run_selection_in_new_tab))
+      .on_action(cx.listener(Self::find_in_terminals))
       .child(self.render_title_bar(cx))
       .child(
         // Main content area
@@ -0,0 +1,256 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::persist;
+
+/// 将 JSON 从版本 n 升级到 n + 1
+pub type Migration = fn(&mut Value) -> Result<(), String>;
+
+/// 带版本的文件格式
+///
+/// `migrations[n]` 将版本 n 升级到 n + 1，当前版本即迁移的个数。
+/// 格式变化时只需追加一个迁移，旧文件在读取时会被逐级升级。
+pub struct Schema {
+  pub name: &'static str,
+  pub migrations: &'static [Migration],
+}
+
+impl Schema {
+  /// 当前版本
+  pub const fn version(&self) -> u32 {
+    self.migrations.len() as u32
+  }
+}
+
+/// 读取带版本文件时的错误
+#[derive(Debug)]
+pub enum LoadError {
+  /// 文件无法读取
+  Io(String),
+  /// 文件不是合法的 JSON（内容为解析错误，不含路径）
+  Parse(String),
+  /// 文件由更新的版本写入
+  TooNew { found: u64, supported: u32 },
+  /// 迁移失败，原文件保持不变
+  Migration(String),
+}
+
+impl fmt::Display for LoadError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      LoadError::Io(e) | LoadError::Parse(e) | LoadError::Migration(e) => f.write_str(e),
+      LoadError::TooNew { found, supported } => write!(
+        f,
+        "written by a newer version (format {}, supported {})",
+        found, supported
+      ),
+    }
+  }
+}
+
+/// 读取带版本的 JSON 文件，必要时升级到当前版本
+///
+/// 升级前将原文件复制为 `<文件名>.v<旧版本>.bak`，升级后原子写回原位置。
+/// 文件不存在时返回 `Ok(None)`。
+pub fn load(path: &Path, schema: &Schema) -> Result<Option<Value>, LoadError> {
+  let contents = match std::fs::read_to_string(path) {
+    Ok(contents) => contents,
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+    Err(e) => {
+      return Err(LoadError::Io(format!(
+        "Failed to read {}: {}",
+        path.display(),
+        e
+      )));
+    }
+  };
+  let mut value: Value =
+    serde_json::from_str(&contents).map_err(|e| LoadError::Parse(e.to_string()))?;
+
+  let found = value.get("version").and_then(Value::as_u64).unwrap_or(0);
+  let current = schema.version();
+  if found > current as u64 {
+    return Err(LoadError::TooNew {
+      found,
+      supported: current,
+    });
+  }
+  if found == current as u64 {
+    return Ok(Some(value));
+  }
+
+  for (version, migration) in schema.migrations.iter().enumerate().skip(found as usize) {
+    migration(&mut value).map_err(|e| {
+      LoadError::Migration(format!(
+        "Failed to migrate {} from version {}: {}",
+        schema.name, version, e
+      ))
+    })?;
+  }
+  let Some(object) = value.as_object_mut() else {
+    return Err(LoadError::Migration(format!(
+      "{} is not a JSON object",
+      path.display()
+    )));
+  };
+  object.insert("version".to_string(), current.into());
+
+  let backup = backup_path(path, found);
+  std::fs::copy(path, &backup)
+    .map_err(|e| LoadError::Migration(format!("Failed to back up {}: {}", path.display(), e)))?;
+  let contents =
+    serde_json::to_vec_pretty(&value).map_err(|e| LoadError::Migration(e.to_string()))?;
+  persist::write_atomic(path, &contents)
+    .map_err(|e| LoadError::Migration(format!("Failed to write {}: {}", path.display(), e)))?;
+  eprintln!(
+    "Migrated {} from version {} to {} (backup at {})",
+    schema.name,
+    found,
+    current,
+    backup.display()
+  );
+
+  Ok(Some(value))
+}
+
+fn backup_path(path: &Path, version: u64) -> PathBuf {
+  let mut name = path.file_name().unwrap_or_default().to_os_string();
+  name.push(format!(".v{}.bak", version));
+  path.with_file_name(name)
+}
+
+/// 只写入版本号的迁移：用于引入版本号之前的文件
+pub fn add_version(_: &mut Value) -> Result<(), String> {
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  /// 版本 1 → 2：给文件加上 `upgraded` 字段
+  fn mark_upgraded(value: &mut Value) -> Result<(), String> {
+    value
+      .as_object_mut()
+      .ok_or("not an object")?
+      .insert("upgraded".to_string(), true.into());
+    Ok(())
+  }
+
+  fn fail(_: &mut Value) -> Result<(), String> {
+    Err("unsupported layout".to_string())
+  }
+
+  const SCHEMA: Schema = Schema {
+    name: "test",
+    migrations: &[add_version, mark_upgraded],
+  };
+
+  const FAILING_SCHEMA: Schema = Schema {
+    name: "test",
+    migrations: &[add_version, fail],
+  };
+
+  /// 每个测试使用独立的临时目录，避免并行运行时互相干扰
+  fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("catus-migration-{}-{}", std::process::id(), name));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+  }
+
+  fn read_json(path: &Path) -> Value {
+    serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+  }
+
+  #[test]
+  fn upgrades_unversioned_file_to_current_version() {
+    let dir = temp_dir("upgrade");
+    let path = dir.join("state.json");
+    std::fs::write(&path, r#"{"name":"old"}"#).unwrap();
+
+    let value = load(&path, &SCHEMA).unwrap().unwrap();
+    let expected = json!({"name": "old", "upgraded": true, "version": 2});
+    assert_eq!(value, expected);
+    assert_eq!(read_json(&path), expected);
+  }
+
+  #[test]
+  fn backs_up_original_before_rewriting() {
+    let dir = temp_dir("backup");
+    let path = dir.join("state.json");
+    let original = r#"{"name":"old","version":1}"#;
+    std::fs::write(&path, original).unwrap();
+
+    load(&path, &SCHEMA).unwrap();
+    let backup = dir.join("state.json.v1.bak");
+    assert_eq!(backup_path(&path, 1), backup);
+    assert_eq!(std::fs::read_to_string(&backup).unwrap(), original);
+    assert_eq!(read_json(&path)["version"], 2);
+  }
+
+  #[test]
+  fn current_version_is_not_rewritten() {
+    let dir = temp_dir("current");
+    let path = dir.join("state.json");
+    let original = r#"{"version":2}"#;
+    std::fs::write(&path, original).unwrap();
+
+    assert_eq!(
+      load(&path, &SCHEMA).unwrap().unwrap(),
+      json!({"version": 2})
+    );
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), original);
+    assert!(!backup_path(&path, 2).exists());
+  }
+
+  #[test]
+  fn too_new_file_is_left_untouched() {
+    let dir = temp_dir("too-new");
+    let path = dir.join("state.json");
+    let original = r#"{"version":7}"#;
+    std::fs::write(&path, original).unwrap();
+
+    assert!(matches!(
+      load(&path, &SCHEMA),
+      Err(LoadError::TooNew {
+        found: 7,
+        supported: 2
+      })
+    ));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), original);
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+  }
+
+  #[test]
+  fn failed_migration_keeps_original_without_backup() {
+    let dir = temp_dir("failed");
+    let path = dir.join("state.json");
+    let original = r#"{"name":"old"}"#;
+    std::fs::write(&path, original).unwrap();
+
+    assert!(matches!(
+      load(&path, &FAILING_SCHEMA),
+      Err(LoadError::Migration(_))
+    ));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), original);
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+  }
+
+  #[test]
+  fn missing_file_is_none() {
+    let dir = temp_dir("missing");
+    assert!(load(&dir.join("state.json"), &SCHEMA).unwrap().is_none());
+  }
+
+  #[test]
+  fn parse_error_is_reported() {
+    let dir = temp_dir("parse");
+    let path = dir.join("state.json");
+    std::fs::write(&path, "{not json").unwrap();
+    assert!(matches!(load(&path, &SCHEMA), Err(LoadError::Parse(_))));
+  }
+}
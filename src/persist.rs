@@ -0,0 +1,63 @@
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+
+/// 配置目录（如 `~/.config/catus`）
+pub fn config_dir() -> Option<PathBuf> {
+  dirs::config_dir().map(|dir| dir.join("catus"))
+}
+
+/// 原子写入文件：先写入同目录下的临时文件并刷盘，再重命名覆盖目标文件，
+/// 崩溃或断电时目标文件要么是旧内容，要么是完整的新内容
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+  let dir = path
+    .parent()
+    .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no parent"))?;
+  fs::create_dir_all(dir)?;
+
+  let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+  tmp_name.push(".tmp");
+  let tmp_path = dir.join(tmp_name);
+
+  let mut file = File::create(&tmp_path)?;
+  file.write_all(contents)?;
+  file.sync_all()?;
+  drop(file);
+
+  fs::rename(&tmp_path, path)?;
+
+  // 刷新目录项，确保重命名本身也已落盘
+  #[cfg(unix)]
+  File::open(dir)?.sync_all()?;
+
+  Ok(())
+}
+
+/// 串行执行同一个文件的写入：后台写入可能乱序完成，比已写入内容更旧的请求直接跳过
+pub struct OrderedWrites {
+  written: Mutex<u64>,
+}
+
+impl OrderedWrites {
+  pub const fn new() -> Self {
+    Self {
+      written: Mutex::new(0),
+    }
+  }
+
+  /// 执行第 `generation` 次请求的写入（请求时递增），跳过时返回 Ok
+  pub fn write(
+    &self,
+    generation: u64,
+    write: impl FnOnce() -> Result<(), String>,
+  ) -> Result<(), String> {
+    let mut written = self.written.lock().unwrap_or_else(PoisonError::into_inner);
+    if *written > generation {
+      return Ok(());
+    }
+    write()?;
+    *written = generation;
+    Ok(())
+  }
+}
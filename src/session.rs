@@ -0,0 +1,286 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use gpui::{App, AppContext as _, Entity, Global, Task};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::migration::{self, LoadError, Schema};
+use crate::persist::{self, OrderedWrites};
+use crate::settings::Settings;
+use crate::workspace::Workspace;
+
+/// 会话文件格式，格式变化时追加迁移
+pub const SESSION_SCHEMA: Schema = Schema {
+  name: "session",
+  migrations: &[migration::add_version, move_tab_state],
+};
+
+/// 会话文件格式版本
+pub const SESSION_VERSION: u32 = SESSION_SCHEMA.version();
+
+/// 持久化的会话：打开的 Tab 及当前激活的 Tab
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+  pub version: u32,
+  pub tabs: Vec<TabSession>,
+  /// 激活的 Tab 在 `tabs` 中的索引
+  pub active_tab: Option<usize>,
+}
+
+/// 单个 Tab 的持久化状态
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TabSession {
+  /// Tab 类型 ID（见 `TabKindDescriptor::id`）
+  pub kind: String,
+  pub title: Option<String>,
+  /// 由 Tab 类型序列化的状态
+  #[serde(default)]
+  pub state: Value,
+}
+
+/// 版本 1 → 2：Tab 的类型相关字段移入 `state`，标题移到外层
+fn move_tab_state(value: &mut Value) -> Result<(), String> {
+  let Some(tabs) = value.get_mut("tabs").and_then(Value::as_array_mut) else {
+    return Ok(());
+  };
+  for tab in tabs {
+    let fields = tab.as_object_mut().ok_or("tab is not an object")?;
+    let kind = fields.remove("kind").ok_or("tab has no kind")?;
+    let title = fields.remove("title").unwrap_or(Value::Null);
+    let state = std::mem::take(fields);
+    *tab = serde_json::json!({
+      "kind": kind,
+      "title": title,
+      "state": state,
+    });
+  }
+  Ok(())
+}
+
+impl Session {
+  pub fn path() -> Option<PathBuf> {
+    persist::config_dir().map(|dir| dir.join("session.json"))
+  }
+
+  /// 读取会话文件
+  ///
+  /// * `Ok(None)` - 文件不存在
+  /// * `Err(String)` - 文件无法读取、无法升级或由更新版本写入
+  pub fn load() -> Result<Option<Self>, String> {
+    let Some(path) = Self::path() else {
+      return Ok(None);
+    };
+    let value = match migration::load(&path, &SESSION_SCHEMA) {
+      Ok(Some(value)) => value,
+      Ok(None) => return Ok(None),
+      Err(LoadError::Parse(e)) => {
+        Self::set_aside(&path, &e);
+        return Ok(None);
+      }
+      Err(e @ LoadError::TooNew { .. }) => {
+        return Err(format!("{} was {}", path.display(), e));
+      }
+      Err(e) => return Err(e.to_string()),
+    };
+    match serde_json::from_value(value) {
+      Ok(session) => Ok(Some(session)),
+      Err(e) => {
+        Self::set_aside(&path, &e.to_string());
+        Ok(None)
+      }
+    }
+  }
+
+  /// 将无法解析的会话文件改名保留，之后的自动保存不会覆盖它
+  fn set_aside(path: &std::path::Path, error: &str) {
+    let corrupt = path.with_extension("json.corrupt");
+    eprintln!(
+      "Failed to parse {}: {}; moved to {}",
+      path.display(),
+      error,
+      corrupt.display()
+    );
+    if let Err(e) = std::fs::rename(path, &corrupt) {
+      eprintln!("Failed to move {}: {}", path.display(), e);
+    }
+  }
+
+  /// 原子写入会话文件
+  pub fn save(&self) -> Result<(), String> {
+    let path = Self::path().ok_or("No config directory")?;
+    let contents = serde_json::to_vec_pretty(self).map_err(|e| e.to_string())?;
+    persist::write_atomic(&path, &contents)
+      .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+  }
+}
+
+/// Tab 结构连续变化时，最后一次变化后等待多久再保存
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// 自动保存状态
+struct SessionAutosave {
+  workspace: Entity<Workspace>,
+  /// 上一次写入的内容，未变化时跳过写入
+  last_saved: Option<Session>,
+  /// 每次写入递增，后台写入乱序完成时据此丢弃旧内容
+  generation: u64,
+  /// 等待防抖结束的保存
+  _pending: Task<()>,
+}
+
+impl Global for SessionAutosave {}
+
+impl SessionAutosave {
+  /// 取出需要写入的会话，内容未变化时返回 None
+  fn take_changed(cx: &mut App) -> Option<(Session, u64)> {
+    let autosave = cx.try_global::<Self>()?;
+    let session = autosave.workspace.read(cx).session(cx);
+    if autosave.last_saved.as_ref() == Some(&session) {
+      return None;
+    }
+    let autosave = cx.global_mut::<Self>();
+    autosave.last_saved = Some(session.clone());
+    autosave.generation += 1;
+    Some((session, autosave.generation))
+  }
+
+  /// 在后台写入会话文件；失败时清除记录，下次保存会重试
+  fn save(cx: &mut App) {
+    let Some((session, generation)) = Self::take_changed(cx) else {
+      return;
+    };
+    cx.spawn(async move |cx| {
+      let write = cx.background_spawn(async move { write_in_order(&session, generation) });
+      if let Err(e) = write.await {
+        eprintln!("Failed to save session: {}", e);
+        let _ = cx.update(|cx| {
+          let autosave = cx.global_mut::<Self>();
+          if autosave.generation == generation {
+            autosave.last_saved = None;
+          }
+        });
+      }
+    })
+    .detach();
+  }
+
+  /// 等待 `SAVE_DEBOUNCE` 后保存，期间再次调用时重新计时
+  fn schedule(cx: &mut App) {
+    let pending = cx.spawn(async move |cx| {
+      cx.background_executor().timer(SAVE_DEBOUNCE).await;
+      let _ = cx.update(Self::save);
+    });
+    if cx.has_global::<Self>() {
+      cx.global_mut::<Self>()._pending = pending;
+    }
+  }
+
+  /// 退出时在当前线程写入，确保写完再退出
+  fn save_now(cx: &mut App) {
+    let Some((session, generation)) = Self::take_changed(cx) else {
+      return;
+    };
+    if let Err(e) = write_in_order(&session, generation) {
+      eprintln!("Failed to save session: {}", e);
+    }
+  }
+}
+
+fn write_in_order(session: &Session, generation: u64) -> Result<(), String> {
+  static WRITES: OrderedWrites = OrderedWrites::new();
+  WRITES.write(generation, || session.save())
+}
+
+/// 启动会话自动保存：Tab 结构变化后（防抖）、按设置的间隔以及退出时保存
+///
+/// `overwrite` 为 false 时（会话文件无法读取或由更新版本写入）不自动保存，避免覆盖原文件
+pub fn start_autosave(workspace: Entity<Workspace>, overwrite: bool, cx: &mut App) {
+  if !overwrite {
+    return;
+  }
+
+  cx.observe(&workspace, |_, cx| SessionAutosave::schedule(cx))
+    .detach();
+  cx.set_global(SessionAutosave {
+    workspace,
+    last_saved: None,
+    generation: 0,
+    _pending: Task::ready(()),
+  });
+
+  if let Some(interval) = cx.global::<Settings>().autosave_interval() {
+    cx.spawn(async move |cx| {
+      loop {
+        cx.background_executor().timer(interval).await;
+        if cx.update(SessionAutosave::save).is_err() {
+          break;
+        }
+      }
+    })
+    .detach();
+  }
+
+  cx.on_app_quit(|cx| {
+    SessionAutosave::save_now(cx);
+    async {}
+  })
+  .detach();
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  /// 版本 1 之前的会话：Tab 是按 `kind` 区分的枚举，类型相关字段与 `kind` 并列
+  fn legacy_session() -> Value {
+    json!({
+      "tabs": [
+        {"kind": "terminal", "title": "zsh"},
+        {"kind": "hex_viewer", "path": "/tmp/a.bin"},
+        {"kind": "stats"},
+      ],
+      "active_tab": 1,
+    })
+  }
+
+  #[test]
+  fn move_tab_state_moves_fields_into_state() {
+    let mut value = legacy_session();
+    move_tab_state(&mut value).unwrap();
+    assert_eq!(
+      value["tabs"],
+      json!([
+        {"kind": "terminal", "title": "zsh", "state": {}},
+        {"kind": "hex_viewer", "title": null, "state": {"path": "/tmp/a.bin"}},
+        {"kind": "stats", "title": null, "state": {}},
+      ])
+    );
+    assert_eq!(value["active_tab"], 1);
+  }
+
+  #[test]
+  fn move_tab_state_rejects_tab_without_kind() {
+    let mut value = json!({"tabs": [{"title": "zsh"}]});
+    assert!(move_tab_state(&mut value).is_err());
+  }
+
+  #[test]
+  fn legacy_session_file_loads_as_current_session() {
+    let dir = std::env::temp_dir().join(format!("catus-session-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("session.json");
+    std::fs::write(&path, legacy_session().to_string()).unwrap();
+
+    let value = migration::load(&path, &SESSION_SCHEMA).unwrap().unwrap();
+    let session: Session = serde_json::from_value(value).unwrap();
+    assert_eq!(session.version, SESSION_VERSION);
+    assert_eq!(session.active_tab, Some(1));
+    assert_eq!(session.tabs[0].title.as_deref(), Some("zsh"));
+    assert_eq!(session.tabs[1].kind, "hex_viewer");
+    assert_eq!(session.tabs[1].state, json!({"path": "/tmp/a.bin"}));
+    assert!(dir.join("session.json.v0.bak").exists());
+  }
+}
@@ -0,0 +1,165 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use gpui::{App, Global};
+use serde::{Deserialize, Serialize};
+
+use crate::migration::{self, LoadError, Schema};
+use crate::persist;
+use crate::terminal::keys::{BackspaceKey, DeleteKey};
+
+/// 设置文件格式，格式变化时追加迁移
+pub const SETTINGS_SCHEMA: Schema = Schema {
+  name: "settings",
+  migrations: &[migration::add_version],
+};
+
+/// 用户设置，从配置目录下的 `settings.json` 读取
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+  pub version: u32,
+  /// 会话自动保存间隔（秒），0 表示只在结构变化和退出时保存
+  pub autosave_interval_secs: u64,
+  /// 新终端启动的 shell，None 时使用 `$SHELL`
+  pub shell: Option<String>,
+  /// 终端中的 sudo -A 和 ssh 通过应用对话框输入密码（仅 Unix），重启应用后生效
+  pub askpass: bool,
+  /// 用 `"$CATUS_BIN" --tag-stderr 命令` 运行的命令，stderr 输出带背景色；对之后新建的终端生效
+  pub stderr_tint: bool,
+  /// Backspace 键发送 `del`（`^?`）还是 `ctrl_h`（`^H`），应用可以通过 DECBKM 切换
+  pub backspace: BackspaceKey,
+  /// Delete 键发送 `escape`（`ESC [ 3 ~`）还是 `del`（`^?`）
+  pub delete: DeleteKey,
+  /// 全屏应用（less、vim 等）中滚轮每一格转换成的方向键次数（1 到 20）
+  pub alternate_scroll_lines: usize,
+  /// 在后台预先启动一个 shell，新建终端时直接使用
+  pub prewarm_terminal: bool,
+  /// 在这些列绘制竖直参考线（如 `[80, 100, 120]`），为空时不显示
+  pub column_guides: Vec<usize>,
+  /// 新建终端时可以选择的环境变量集
+  pub environments: Vec<EnvironmentSet>,
+  /// 按主机设置终端背景色调；在终端中输入 `ssh 主机` 后自动应用，ssh 断开后恢复
+  pub host_tints: Vec<HostTint>,
+  /// 设置文件存在但无法读取时的错误；此时使用的是默认值，不能写回覆盖原文件
+  #[serde(skip)]
+  pub load_error: Option<String>,
+}
+
+/// 主机的背景色调，如生产环境用暗红色
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HostTint {
+  /// 主机名，`*` 匹配任意字符，如 `prod-*`
+  pub host: String,
+  pub tint: [u8; 3],
+}
+
+/// 命名的环境变量集，如 “AWS prod”，选择后注入新终端的 shell
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnvironmentSet {
+  pub name: String,
+  pub vars: BTreeMap<String, String>,
+  /// 终端顶部横幅的颜色，提醒当前所在的环境
+  pub color: Option<[u8; 3]>,
+  /// 终端的背景色调，连接的主机有自己的色调时以主机为准
+  pub tint: Option<[u8; 3]>,
+  /// 覆盖设置中的 Backspace 和 Delete 键，用于需要 `^H` 的系统
+  pub backspace: Option<BackspaceKey>,
+  pub delete: Option<DeleteKey>,
+  /// 用于连接不受信任的系统，终端按不受信任的输出处理（见 `TerminalOptions::untrusted`）
+  pub untrusted: bool,
+}
+
+impl Default for Settings {
+  fn default() -> Self {
+    Self {
+      version: SETTINGS_SCHEMA.version(),
+      autosave_interval_secs: 30,
+      shell: None,
+      askpass: false,
+      stderr_tint: false,
+      backspace: BackspaceKey::default(),
+      delete: DeleteKey::default(),
+      alternate_scroll_lines: 3,
+      prewarm_terminal: true,
+      column_guides: Vec::new(),
+      environments: Vec::new(),
+      host_tints: Vec::new(),
+      load_error: None,
+    }
+  }
+}
+
+impl Global for Settings {}
+
+impl Settings {
+  pub fn path() -> Option<PathBuf> {
+    persist::config_dir().map(|dir| dir.join("settings.json"))
+  }
+
+  /// 读取设置，旧格式会先升级；文件不存在或无法解析时使用默认值，无法解析时记下错误
+  pub fn load() -> Self {
+    let Some(path) = Self::path() else {
+      return Self::default();
+    };
+    let failed = |error: String| {
+      eprintln!("{}", error);
+      Self {
+        load_error: Some(error),
+        ..Self::default()
+      }
+    };
+    let value = match migration::load(&path, &SETTINGS_SCHEMA) {
+      Ok(Some(value)) => value,
+      Ok(None) => return Self::default(),
+      Err(LoadError::Parse(e)) => {
+        return failed(format!("Failed to parse {}: {}", path.display(), e));
+      }
+      Err(e @ LoadError::TooNew { .. }) => {
+        return failed(format!("{} was {}; using defaults", path.display(), e));
+      }
+      Err(e) => return failed(e.to_string()),
+    };
+    serde_json::from_value(value)
+      .unwrap_or_else(|e| failed(format!("Failed to parse {}: {}", path.display(), e)))
+  }
+
+  /// 写回设置文件；读取时出错的设置不写回，避免用默认值覆盖用户的文件
+  pub fn save(&self) -> Result<(), String> {
+    if let Some(error) = &self.load_error {
+      return Err(format!(
+        "Settings were not saved because the file could not be loaded. {}",
+        error
+      ));
+    }
+    let path = Self::path().ok_or("No config directory")?;
+    let contents = serde_json::to_vec_pretty(self).map_err(|e| e.to_string())?;
+    persist::write_atomic(&path, &contents)
+      .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+  }
+
+  /// 新终端实际启动的 shell
+  pub fn shell_path(&self) -> PathBuf {
+    self
+      .shell
+      .clone()
+      .or_else(|| std::env::var("SHELL").ok())
+      .unwrap_or_else(|| "/bin/sh".to_string())
+      .into()
+  }
+
+  pub fn environment(&self, name: &str) -> Option<&EnvironmentSet> {
+    self.environments.iter().find(|set| set.name == name)
+  }
+
+  pub fn autosave_interval(&self) -> Option<Duration> {
+    (self.autosave_interval_secs > 0).then(|| Duration::from_secs(self.autosave_interval_secs))
+  }
+}
+
+pub fn init(cx: &mut App) {
+  cx.set_global(Settings::load());
+}
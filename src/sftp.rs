@@ -0,0 +1,38 @@
+use gpui::*;
+use gpui_component::IconName;
+
+use crate::tab_kind::{self, TabKindDescriptor, TabKinds};
+
+/// SFTP Tab 类型 (TODO: 实现)
+pub static SFTP_TAB_KIND: TabKindDescriptor = TabKindDescriptor {
+  id: "sftp",
+  icon: IconName::Folder,
+  menu_label: Some("SFTP"),
+  prompt: None,
+  build: |_, _, cx| Ok(cx.new(|_| SftpView).into()),
+  title: |_, _| "SFTP".into(),
+  detail: |_, _| Some("Not connected".into()),
+  focus_handle: tab_kind::no_focus,
+  serialize: tab_kind::no_state,
+};
+
+/// SFTP 视图（占位）
+pub struct SftpView;
+
+impl Render for SftpView {
+  fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+    // TODO: 实现 SFTP 视图
+    div()
+      .flex_1()
+      .size_full()
+      .flex()
+      .items_center()
+      .justify_center()
+      .child("SFTP view not implemented yet")
+  }
+}
+
+/// 注册 SFTP Tab 类型
+pub fn init(cx: &mut App) {
+  TabKinds::register(&SFTP_TAB_KIND, cx);
+}
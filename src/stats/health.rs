@@ -0,0 +1,362 @@
+use std::io::Read as _;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use gpui::{App, AppContext as _, ClipboardItem, Global, Window};
+use gpui_component::button::{Button, ButtonVariants as _};
+use gpui_component::notification::Notification;
+use gpui_component::{Root, Sizable as _, WindowExt as _};
+
+use crate::main_view::MainView;
+use crate::settings::Settings;
+use crate::stats::health_view::HEALTH_TAB_KIND;
+use crate::terminal::integration;
+
+/// 检查 TERM 时等待交互式 shell 启动的最长时间
+const SHELL_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+/// 终端设置的 TERM
+const EXPECTED_TERM: &str = "xterm-256color";
+
+/// 检查的触发方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Trigger {
+  /// 应用启动时：不启动交互式 shell，发现问题时弹出通知
+  Startup,
+  /// 用户重新检查或执行修复后
+  User,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheckStatus {
+  Ok,
+  Warning,
+  /// 无法判断或不适用
+  Skipped,
+}
+
+/// 检查发现问题时的一键修复
+#[derive(Clone, Debug)]
+pub enum Fix {
+  /// 写入集成脚本并在 shell 启动文件中加载
+  InstallIntegration(PathBuf),
+  /// 在设置中改用另一个 shell
+  UseShell(String),
+  /// 复制需要用户自己执行的命令
+  CopyText(String),
+}
+
+impl Fix {
+  pub fn label(&self) -> String {
+    match self {
+      Fix::InstallIntegration(_) => "Install Integration".to_string(),
+      Fix::UseShell(shell) => format!("Use {}", shell),
+      Fix::CopyText(_) => "Copy Fix".to_string(),
+    }
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct HealthCheck {
+  pub title: &'static str,
+  pub status: CheckStatus,
+  pub message: String,
+  pub fix: Option<Fix>,
+}
+
+impl HealthCheck {
+  fn ok(title: &'static str, message: impl Into<String>) -> Self {
+    Self {
+      title,
+      status: CheckStatus::Ok,
+      message: message.into(),
+      fix: None,
+    }
+  }
+
+  fn warning(title: &'static str, message: impl Into<String>, fix: Option<Fix>) -> Self {
+    Self {
+      title,
+      status: CheckStatus::Warning,
+      message: message.into(),
+      fix,
+    }
+  }
+
+  fn skipped(title: &'static str, message: impl Into<String>) -> Self {
+    Self {
+      title,
+      status: CheckStatus::Skipped,
+      message: message.into(),
+      fix: None,
+    }
+  }
+}
+
+/// 最近一次检查的结果，检查进行中时 `running` 为 true
+#[derive(Default)]
+pub struct HealthReport {
+  pub checks: Vec<HealthCheck>,
+  pub running: bool,
+}
+
+impl Global for HealthReport {}
+
+impl HealthReport {
+  pub fn warnings(&self) -> usize {
+    self
+      .checks
+      .iter()
+      .filter(|check| check.status == CheckStatus::Warning)
+      .count()
+  }
+}
+
+/// 运行所有检查；用户触发时会启动交互式 shell，需要在后台线程调用
+pub fn run_checks(shell: &Path, trigger: Trigger) -> Vec<HealthCheck> {
+  if cfg!(windows) {
+    return Vec::new();
+  }
+  let shell_ok = shell_is_executable(shell);
+  let term = if !shell_ok {
+    HealthCheck::skipped("TERM", "Skipped because the shell could not be started")
+  } else if trigger == Trigger::Startup {
+    // 交互式 shell 会执行用户的启动文件，只在用户要求时运行
+    HealthCheck::skipped(
+      "TERM",
+      "Run the checks again to start your shell and check TERM",
+    )
+  } else {
+    check_term(shell)
+  };
+  vec![
+    check_shell(shell, shell_ok),
+    check_locale(),
+    check_integration(shell),
+    term,
+  ]
+}
+
+fn shell_is_executable(shell: &Path) -> bool {
+  let Ok(metadata) = std::fs::metadata(shell) else {
+    return false;
+  };
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::PermissionsExt as _;
+    metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+  }
+  #[cfg(not(unix))]
+  metadata.is_file()
+}
+
+fn check_shell(shell: &Path, executable: bool) -> HealthCheck {
+  const TITLE: &str = "Shell";
+  if executable {
+    return HealthCheck::ok(TITLE, format!("{} is available", shell.display()));
+  }
+  let fallback = ["/bin/bash", "/bin/zsh", "/bin/sh"]
+    .into_iter()
+    .find(|candidate| Path::new(candidate) != shell && shell_is_executable(Path::new(candidate)));
+  HealthCheck::warning(
+    TITLE,
+    format!(
+      "{} does not exist or is not executable; new terminals will fail to start",
+      shell.display()
+    ),
+    fallback.map(|shell| Fix::UseShell(shell.to_string())),
+  )
+}
+
+fn check_locale() -> HealthCheck {
+  const TITLE: &str = "Locale";
+  // 按 POSIX 的优先级取生效的字符集设置
+  let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+    .into_iter()
+    .find_map(|name| std::env::var(name).ok().filter(|value| !value.is_empty()));
+  match locale {
+    Some(locale) if is_utf8_locale(&locale) => {
+      HealthCheck::ok(TITLE, format!("{} uses UTF-8", locale))
+    }
+    locale => HealthCheck::warning(
+      TITLE,
+      format!(
+        "{} is not UTF-8; programs may print non-ASCII text as question marks",
+        locale.as_deref().unwrap_or("The locale")
+      ),
+      Some(Fix::CopyText("export LANG=en_US.UTF-8".to_string())),
+    ),
+  }
+}
+
+fn is_utf8_locale(locale: &str) -> bool {
+  let locale = locale.to_ascii_lowercase();
+  locale.contains("utf-8") || locale.contains("utf8")
+}
+
+fn check_integration(shell: &Path) -> HealthCheck {
+  const TITLE: &str = "Shell integration";
+  if integration::rc_path(shell).is_none() {
+    return HealthCheck::skipped(TITLE, format!("Not available for {}", shell.display()));
+  }
+  if integration::is_installed(shell) {
+    HealthCheck::ok(TITLE, "Installed")
+  } else {
+    HealthCheck::warning(
+      TITLE,
+      "Not installed; catus_run and other helpers are unavailable in the shell",
+      Some(Fix::InstallIntegration(shell.to_path_buf())),
+    )
+  }
+}
+
+/// 启动交互式 shell 读取 TERM，确认启动文件没有覆盖终端设置的值
+///
+/// shell 在单独的进程组中运行，结束时连同启动文件启动的进程一起终止。
+fn check_term(shell: &Path) -> HealthCheck {
+  const TITLE: &str = "TERM";
+  let mut command = Command::new(shell);
+  command
+    .args(["-i", "-c", "printf '\\n%s' \"$TERM\""])
+    .env("TERM", EXPECTED_TERM)
+    .stdin(Stdio::null())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::null());
+  #[cfg(unix)]
+  std::os::unix::process::CommandExt::process_group(&mut command, 0);
+  let child = command.spawn();
+  let Ok(mut child) = child else {
+    return HealthCheck::skipped(TITLE, "Could not start the shell");
+  };
+
+  // 在单独的线程读取输出：启动文件中的后台进程可能继承 stdout，读取会一直阻塞
+  let (output_tx, output_rx) = mpsc::channel();
+  if let Some(mut stdout) = child.stdout.take() {
+    std::thread::spawn(move || {
+      let mut output = String::new();
+      let _ = stdout.read_to_string(&mut output);
+      let _ = output_tx.send(output);
+    });
+  }
+  let output = output_rx.recv_timeout(SHELL_PROBE_TIMEOUT);
+  #[cfg(unix)]
+  if let Ok(pgid) = libc::pid_t::try_from(child.id()) {
+    unsafe { libc::kill(-pgid, libc::SIGKILL) };
+  }
+  let _ = child.kill();
+  let _ = child.wait();
+  let Ok(output) = output else {
+    return HealthCheck::skipped(TITLE, "The shell took too long to start");
+  };
+
+  // 启动文件可能打印其他内容，取最后一行
+  let term = output.lines().last().unwrap_or_default().trim();
+  if term == EXPECTED_TERM {
+    HealthCheck::ok(TITLE, format!("{} is respected", EXPECTED_TERM))
+  } else {
+    HealthCheck::warning(
+      TITLE,
+      format!(
+        "Your shell startup files change TERM to \"{}\"; colors and keys may not work. Remove the TERM assignment from them.",
+        term
+      ),
+      None,
+    )
+  }
+}
+
+/// 在后台运行检查，完成后更新 `HealthReport`；启动时发现问题会弹出通知
+pub fn refresh(trigger: Trigger, cx: &mut App) {
+  if cx.default_global::<HealthReport>().running {
+    return;
+  }
+  cx.global_mut::<HealthReport>().running = true;
+  let shell = cx.global::<Settings>().shell_path();
+  let task = cx.background_spawn(async move { run_checks(&shell, trigger) });
+  cx.spawn(async move |cx| {
+    let checks = task.await;
+    let _ = cx.update(|cx| {
+      let report = cx.global_mut::<HealthReport>();
+      report.checks = checks;
+      report.running = false;
+      let warnings = report.warnings();
+      if trigger == Trigger::Startup && warnings > 0 {
+        notify_warnings(warnings, cx);
+      }
+    });
+  })
+  .detach();
+}
+
+fn notify_warnings(warnings: usize, cx: &mut App) {
+  let Some(window) = cx.active_window().or_else(|| cx.windows().first().copied()) else {
+    return;
+  };
+  let _ = window.update(cx, |_, window, cx| {
+    window.push_notification(
+      Notification::warning(format!("Found {} setup problem(s).", warnings)).action(
+        |_, _window, _cx| {
+          Button::new("open-setup-check")
+            .small()
+            .primary()
+            .label("Open Setup Check")
+            .on_click(|_, window, cx| open_setup_check(window, cx))
+        },
+      ),
+      cx,
+    );
+  });
+}
+
+/// 在窗口中打开环境检查 Tab
+fn open_setup_check(window: &mut Window, cx: &mut App) {
+  let Some(Some(root)) = window.root::<Root>() else {
+    return;
+  };
+  let Ok(main_view) = root.read(cx).view().clone().downcast::<MainView>() else {
+    return;
+  };
+  main_view.update(cx, |main_view, cx| {
+    main_view.add_tab(&HEALTH_TAB_KIND, None, window, cx);
+  });
+}
+
+/// 执行修复，完成后重新检查
+pub fn apply(fix: &Fix, window: &mut Window, cx: &mut App) {
+  let result = match fix {
+    Fix::InstallIntegration(shell) => integration::install(shell).map(|rc| {
+      format!(
+        "Added shell integration to {}. Open a new terminal to use it.",
+        rc.display()
+      )
+    }),
+    Fix::UseShell(shell) => {
+      // 写入成功后才修改当前设置，设置文件无法读取时不会被默认值覆盖
+      let mut settings = cx.global::<Settings>().clone();
+      settings.shell = Some(shell.clone());
+      settings.save().map(|_| {
+        *cx.global_mut::<Settings>() = settings;
+        format!("New terminals will start {}", shell)
+      })
+    }
+    Fix::CopyText(text) => {
+      cx.write_to_clipboard(ClipboardItem::new_string(text.clone()));
+      Ok(format!(
+        "Copied \"{}\". Add it to your shell profile.",
+        text
+      ))
+    }
+  };
+  let notification = match result {
+    Ok(message) => Notification::success(message),
+    Err(e) => Notification::error(e),
+  };
+  window.push_notification(notification, cx);
+  refresh(Trigger::User, cx);
+}
+
+/// 启动时运行一次检查
+pub fn init(cx: &mut App) {
+  refresh(Trigger::Startup, cx);
+}
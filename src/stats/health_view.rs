@@ -0,0 +1,95 @@
+use gpui::*;
+use gpui_component::button::{Button, ButtonVariants as _};
+use gpui_component::{
+  ActiveTheme as _, Disableable as _, Icon, IconName, Sizable as _, StyledExt as _, h_flex, v_flex,
+};
+
+use crate::stats::health::{self, CheckStatus, HealthReport, Trigger};
+use crate::tab_kind::{self, TabKindDescriptor};
+
+/// 环境检查 Tab 类型
+pub static HEALTH_TAB_KIND: TabKindDescriptor = TabKindDescriptor {
+  id: "health",
+  icon: IconName::CircleCheck,
+  menu_label: Some("Setup Check"),
+  prompt: None,
+  build: |_, _, cx| Ok(cx.new(HealthView::new).into()),
+  title: |_, _| "Setup Check".into(),
+  detail: tab_kind::no_detail,
+  focus_handle: tab_kind::no_focus,
+  serialize: tab_kind::no_state,
+};
+
+/// 环境检查面板：显示检查结果和一键修复
+pub struct HealthView {
+  _observe_health: Subscription,
+}
+
+impl HealthView {
+  pub fn new(cx: &mut Context<Self>) -> Self {
+    Self {
+      _observe_health: cx.observe_global::<HealthReport>(|_, cx| cx.notify()),
+    }
+  }
+}
+
+impl Render for HealthView {
+  fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    // 只读访问，default_global 会触发全局观察者导致反复重绘
+    let (running, checks) = cx
+      .try_global::<HealthReport>()
+      .map(|report| (report.running, report.checks.clone()))
+      .unwrap_or_default();
+    let rows = checks.into_iter().enumerate().map(|(index, check)| {
+      let (icon, color) = match check.status {
+        CheckStatus::Ok => (IconName::CircleCheck, cx.theme().success),
+        CheckStatus::Warning => (IconName::TriangleAlert, cx.theme().warning),
+        CheckStatus::Skipped => (IconName::Minus, cx.theme().muted_foreground),
+      };
+      h_flex()
+        .gap_2()
+        .py_1()
+        .child(Icon::new(icon).small().text_color(color))
+        .child(div().w(px(130.)).flex_shrink_0().child(check.title))
+        .child(
+          div()
+            .flex_1()
+            .text_color(cx.theme().muted_foreground)
+            .child(check.message),
+        )
+        .children(check.fix.map(|fix| {
+          Button::new(("health-fix", index))
+            .xsmall()
+            .primary()
+            .label(fix.label())
+            .on_click(move |_, window, cx| health::apply(&fix, window, cx))
+        }))
+    });
+
+    v_flex()
+      .size_full()
+      .p_4()
+      .gap_3()
+      .child(
+        h_flex()
+          .gap_2()
+          .child(div().text_lg().font_semibold().child("Setup Check"))
+          .child(div().flex_1())
+          .child(
+            Button::new("rerun-health")
+              .small()
+              .ghost()
+              .label(if running { "Checking…" } else { "Run Again" })
+              .disabled(running)
+              .on_click(|_, _window, cx| health::refresh(Trigger::User, cx)),
+          ),
+      )
+      .child(
+        div()
+          .text_sm()
+          .text_color(cx.theme().muted_foreground)
+          .child("Checks that the shell, locale and shell integration are set up for catus."),
+      )
+      .child(v_flex().text_sm().children(rows))
+  }
+}
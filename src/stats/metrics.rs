@@ -0,0 +1,116 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::persist;
+
+/// 本地诊断计数器
+///
+/// 只保存在进程内存中，不会发送到任何地方；用户可以主动导出为 JSON 附在问题报告里。
+/// 计数器是全局原子变量，后台任务和绘制代码都可以直接记录，无需访问 App。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Metric {
+  /// 终端元素绘制的帧数
+  FramesPainted,
+  /// 终端解析的输出字节数
+  BytesParsed,
+}
+
+impl Metric {
+  pub const ALL: [Metric; 2] = [Metric::FramesPainted, Metric::BytesParsed];
+
+  /// 导出 JSON 中使用的键
+  pub fn key(self) -> &'static str {
+    match self {
+      Metric::FramesPainted => "frames_painted",
+      Metric::BytesParsed => "bytes_parsed",
+    }
+  }
+
+  pub fn label(self) -> &'static str {
+    match self {
+      Metric::FramesPainted => "Frames painted",
+      Metric::BytesParsed => "Bytes parsed",
+    }
+  }
+
+  /// 累加计数
+  pub fn record(self, count: u64) {
+    COUNTERS[self as usize].fetch_add(count, Ordering::Relaxed);
+  }
+
+  pub fn value(self) -> u64 {
+    COUNTERS[self as usize].load(Ordering::Relaxed)
+  }
+}
+
+static COUNTERS: [AtomicU64; Metric::ALL.len()] = [const { AtomicU64::new(0) }; Metric::ALL.len()];
+/// 开始计数的时间（启动或上次清零）
+static STARTED: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// 记录开始计数的时间，启动时调用
+pub fn init() {
+  if let Ok(mut started) = STARTED.lock() {
+    started.get_or_insert_with(Instant::now);
+  }
+}
+
+/// 清零所有计数器
+pub fn reset() {
+  for counter in &COUNTERS {
+    counter.store(0, Ordering::Relaxed);
+  }
+  if let Ok(mut started) = STARTED.lock() {
+    *started = Some(Instant::now());
+  }
+}
+
+/// 计数的持续时间（秒）
+pub fn uptime_secs() -> u64 {
+  STARTED
+    .lock()
+    .ok()
+    .and_then(|started| *started)
+    .map_or(0, |started| started.elapsed().as_secs())
+}
+
+/// 导出的指标
+#[derive(Serialize)]
+pub struct MetricsSnapshot {
+  pub app_version: &'static str,
+  pub os: &'static str,
+  pub arch: &'static str,
+  /// 导出时间（Unix 时间戳，秒）
+  pub exported_at: u64,
+  /// 计数持续的秒数
+  pub uptime_secs: u64,
+  pub counters: BTreeMap<&'static str, u64>,
+}
+
+pub fn snapshot() -> MetricsSnapshot {
+  MetricsSnapshot {
+    app_version: env!("CARGO_PKG_VERSION"),
+    os: std::env::consts::OS,
+    arch: std::env::consts::ARCH,
+    exported_at: SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|d| d.as_secs())
+      .unwrap_or(0),
+    uptime_secs: uptime_secs(),
+    counters: Metric::ALL
+      .iter()
+      .map(|metric| (metric.key(), metric.value()))
+      .collect(),
+  }
+}
+
+/// 将当前指标写入 JSON 文件
+pub fn export(path: &Path) -> Result<(), String> {
+  let contents = serde_json::to_vec_pretty(&snapshot()).map_err(|e| e.to_string())?;
+  persist::write_atomic(path, &contents)
+    .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
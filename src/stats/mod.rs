@@ -0,0 +1,17 @@
+pub mod health;
+pub mod health_view;
+pub mod metrics;
+pub mod session_stats;
+pub mod view;
+
+// 重导出主要类型
+pub use metrics::Metric;
+
+/// 注册全局统计状态（从统计文件读取）、统计和环境检查 Tab 类型
+pub fn init(cx: &mut gpui::App) {
+  metrics::init();
+  health::init(cx);
+  crate::tab_kind::TabKinds::register(&view::STATS_TAB_KIND, cx);
+  crate::tab_kind::TabKinds::register(&health_view::HEALTH_TAB_KIND, cx);
+  session_stats::init(cx);
+}
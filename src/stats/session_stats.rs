@@ -0,0 +1,337 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use gpui::{App, AppContext as _, BorrowAppContext, Global};
+use serde::{Deserialize, Serialize};
+
+use crate::migration::{self, LoadError, Schema};
+use crate::persist::{self, OrderedWrites};
+
+/// 统计文件格式，格式变化时追加迁移
+const STATS_SCHEMA: Schema = Schema {
+  name: "stats",
+  migrations: &[migration::add_version],
+};
+/// 有新数据时写入统计文件的间隔
+const SAVE_INTERVAL: Duration = Duration::from_secs(60);
+/// 排行中显示的主机和目录个数
+pub const TOP_COUNT: usize = 5;
+
+/// 一天的统计数据
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DayStats {
+  /// 执行的命令数（按提交的非空输入行估算，包括粘贴、输入框和执行选中内容）
+  pub commands: u64,
+  /// 终端输出的字节数
+  pub output_bytes: u64,
+  /// 在各个 ssh 主机上执行的命令数
+  #[serde(default)]
+  pub hosts: BTreeMap<String, u64>,
+  /// 在各个本地目录中执行的命令数
+  #[serde(default)]
+  pub directories: BTreeMap<String, u64>,
+}
+
+impl DayStats {
+  fn merge(&mut self, other: &DayStats) {
+    self.commands += other.commands;
+    self.output_bytes += other.output_bytes;
+    for (host, count) in &other.hosts {
+      *self.hosts.entry(host.clone()).or_default() += count;
+    }
+    for (directory, count) in &other.directories {
+      *self.directories.entry(directory.clone()).or_default() += count;
+    }
+  }
+}
+
+/// 一条执行的命令，由终端后台任务在写入 PTY 时记录
+#[derive(Clone, Debug, Default)]
+pub struct CommandRecord {
+  /// 通过 ssh 连接的主机，本地命令为 None
+  pub host: Option<String>,
+  /// 本地 shell 的工作目录，无法得知时为 None
+  pub directory: Option<String>,
+}
+
+/// 终端后台任务累计、由 UI 任务取走计入会话统计的数据
+#[derive(Default)]
+pub struct PendingStats {
+  output_bytes: AtomicU64,
+  commands: Mutex<Vec<CommandRecord>>,
+}
+
+impl PendingStats {
+  pub fn add_output(&self, bytes: u64) {
+    self.output_bytes.fetch_add(bytes, Ordering::Relaxed);
+  }
+
+  pub fn add_commands(&self, record: CommandRecord, count: usize) {
+    if let Ok(mut commands) = self.commands.lock() {
+      commands.extend(std::iter::repeat_n(record, count));
+    }
+  }
+
+  /// 取走累计的数据并计入会话统计
+  pub fn flush(&self, cx: &mut App) {
+    SessionStats::record_output(self.output_bytes.swap(0, Ordering::Relaxed), cx);
+    let commands = self
+      .commands
+      .lock()
+      .map(|mut commands| std::mem::take(&mut *commands))
+      .unwrap_or_default();
+    SessionStats::record_commands(commands, cx);
+  }
+}
+
+/// 统计文件的内容
+#[derive(Serialize, Deserialize)]
+struct StatsFile {
+  version: u32,
+  enabled: bool,
+  days: BTreeMap<u64, DayStats>,
+}
+
+/// 会话统计 - 仅在本地记录，需要用户主动开启
+///
+/// 保存在配置目录下的 `stats.json`，有新数据时定期在后台写入，退出时再写入一次。
+#[derive(Default)]
+pub struct SessionStats {
+  /// 是否开启统计（默认关闭）
+  pub enabled: bool,
+  /// 按天（UTC，自 1970-01-01 起的天数）记录的统计数据
+  days: BTreeMap<u64, DayStats>,
+  /// 有尚未写入文件的变化
+  dirty: bool,
+  /// 每次写入递增，后台写入乱序完成时据此丢弃旧内容
+  generation: u64,
+}
+
+impl Global for SessionStats {}
+
+impl SessionStats {
+  fn path() -> Option<PathBuf> {
+    persist::config_dir().map(|dir| dir.join("stats.json"))
+  }
+
+  /// 读取统计文件；不存在或无法读取时从空白开始（统计关闭）
+  fn load() -> Self {
+    let Some(path) = Self::path() else {
+      return Self::default();
+    };
+    let value = match migration::load(&path, &STATS_SCHEMA) {
+      Ok(Some(value)) => value,
+      Ok(None) => return Self::default(),
+      Err(LoadError::Parse(e)) => {
+        eprintln!("Failed to parse {}: {}", path.display(), e);
+        return Self::default();
+      }
+      Err(e) => {
+        eprintln!("{}", e);
+        return Self::default();
+      }
+    };
+    match serde_json::from_value::<StatsFile>(value) {
+      Ok(file) => Self {
+        enabled: file.enabled,
+        days: file.days,
+        ..Self::default()
+      },
+      Err(e) => {
+        eprintln!("Failed to parse {}: {}", path.display(), e);
+        Self::default()
+      }
+    }
+  }
+
+  /// 取出需要写入的内容，没有变化时返回 None
+  fn take_changed(&mut self) -> Option<(Vec<u8>, u64)> {
+    if !self.dirty {
+      return None;
+    }
+    self.dirty = false;
+    self.generation += 1;
+    let file = StatsFile {
+      version: STATS_SCHEMA.version(),
+      enabled: self.enabled,
+      days: self.days.clone(),
+    };
+    match serde_json::to_vec_pretty(&file) {
+      Ok(contents) => Some((contents, self.generation)),
+      Err(e) => {
+        eprintln!("Failed to save statistics: {}", e);
+        None
+      }
+    }
+  }
+
+  /// 在后台写入统计文件，写入失败且之后没有新的写入时重新标记为有变化，下次定期保存时重试
+  fn save(cx: &mut App) {
+    let Some((contents, generation)) = cx.global_mut::<Self>().take_changed() else {
+      return;
+    };
+    cx.spawn(async move |cx| {
+      let write = cx.background_spawn(async move { write_in_order(&contents, generation) });
+      if let Err(e) = write.await {
+        eprintln!("Failed to save statistics: {}", e);
+        let _ = cx.update(|cx| {
+          let stats = cx.global_mut::<Self>();
+          if stats.generation == generation {
+            stats.dirty = true;
+          }
+        });
+      }
+    })
+    .detach();
+  }
+
+  /// 记录执行的命令
+  pub fn record_commands(commands: Vec<CommandRecord>, cx: &mut App) {
+    if commands.is_empty() {
+      return;
+    }
+    Self::record(cx, |day| {
+      for command in commands {
+        day.commands += 1;
+        if let Some(host) = command.host {
+          *day.hosts.entry(host).or_default() += 1;
+        }
+        if let Some(directory) = command.directory {
+          *day.directories.entry(directory).or_default() += 1;
+        }
+      }
+    });
+  }
+
+  /// 记录终端输出的字节数
+  pub fn record_output(bytes: u64, cx: &mut App) {
+    if bytes > 0 {
+      Self::record(cx, |day| day.output_bytes += bytes);
+    }
+  }
+
+  fn record(cx: &mut App, f: impl FnOnce(&mut DayStats)) {
+    if !cx.try_global::<Self>().is_some_and(|stats| stats.enabled) {
+      return;
+    }
+    cx.update_global::<Self, _>(|stats, _cx| {
+      f(stats.days.entry(today()).or_default());
+      stats.dirty = true;
+    });
+  }
+
+  /// 开启或关闭统计
+  pub fn set_enabled(&mut self, enabled: bool) {
+    self.enabled = enabled;
+    self.dirty = true;
+  }
+
+  /// 清除所有统计数据
+  pub fn clear(&mut self) {
+    self.days.clear();
+    self.dirty = true;
+  }
+
+  /// 按日期倒序返回每天的统计数据
+  pub fn days(&self) -> impl Iterator<Item = (u64, &DayStats)> {
+    self.days.iter().rev().map(|(day, stats)| (*day, stats))
+  }
+
+  /// 所有天数的汇总
+  pub fn total(&self) -> DayStats {
+    self
+      .days
+      .values()
+      .fold(DayStats::default(), |mut total, day| {
+        total.merge(day);
+        total
+      })
+  }
+}
+
+/// 执行命令最多的 `TOP_COUNT` 项，次数相同时按名称排序
+pub fn top(counts: &BTreeMap<String, u64>) -> Vec<(&str, u64)> {
+  let mut entries: Vec<_> = counts
+    .iter()
+    .map(|(name, count)| (name.as_str(), *count))
+    .collect();
+  entries.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+  entries.truncate(TOP_COUNT);
+  entries
+}
+
+fn write_in_order(contents: &[u8], generation: u64) -> Result<(), String> {
+  static WRITES: OrderedWrites = OrderedWrites::new();
+  WRITES.write(generation, || {
+    let path = SessionStats::path().ok_or("No config directory")?;
+    persist::write_atomic(&path, contents)
+      .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+  })
+}
+
+/// 读取统计文件，定期和退出时保存
+pub fn init(cx: &mut App) {
+  cx.set_global(SessionStats::load());
+
+  cx.spawn(async move |cx| {
+    loop {
+      cx.background_executor().timer(SAVE_INTERVAL).await;
+      if cx.update(SessionStats::save).is_err() {
+        break;
+      }
+    }
+  })
+  .detach();
+
+  cx.on_app_quit(|cx| {
+    if let Some((contents, generation)) = cx.global_mut::<SessionStats>().take_changed()
+      && let Err(e) = write_in_order(&contents, generation)
+    {
+      eprintln!("Failed to save statistics: {}", e);
+    }
+    async {}
+  })
+  .detach();
+}
+
+/// 今天（UTC）自 1970-01-01 起的天数
+fn today() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_secs() / 86_400)
+    .unwrap_or(0)
+}
+
+/// 将自 1970-01-01 起的天数格式化为 YYYY-MM-DD
+pub fn format_day(day: u64) -> String {
+  // Howard Hinnant 的 civil_from_days 算法
+  let z = day as i64 + 719_468;
+  let era = z.div_euclid(146_097);
+  let doe = z.rem_euclid(146_097);
+  let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+  let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+  let mp = (5 * doy + 2) / 153;
+  let d = doy - (153 * mp + 2) / 5 + 1;
+  let m = if mp < 10 { mp + 3 } else { mp - 9 };
+  let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+  format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+/// 将字节数格式化为易读的字符串
+pub fn format_bytes(bytes: u64) -> String {
+  const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+  let mut value = bytes as f64;
+  let mut unit = 0;
+  while value >= 1024. && unit < UNITS.len() - 1 {
+    value /= 1024.;
+    unit += 1;
+  }
+  if unit == 0 {
+    format!("{} {}", bytes, UNITS[0])
+  } else {
+    format!("{:.1} {}", value, UNITS[unit])
+  }
+}
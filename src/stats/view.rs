@@ -0,0 +1,267 @@
+use std::time::Duration;
+
+use gpui::*;
+use gpui_component::button::{Button, ButtonVariants as _};
+use gpui_component::notification::Notification;
+use gpui_component::{
+  ActiveTheme as _, IconName, Sizable as _, StyledExt as _, WindowExt as _, h_flex, v_flex,
+};
+
+use crate::stats::metrics::{self, Metric};
+use crate::stats::session_stats::{self, SessionStats, format_bytes, format_day};
+use crate::tab_kind::{self, TabKindDescriptor};
+
+/// 诊断计数器的刷新间隔
+const METRICS_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 会话统计 Tab 类型
+pub static STATS_TAB_KIND: TabKindDescriptor = TabKindDescriptor {
+  id: "stats",
+  icon: IconName::ChartPie,
+  menu_label: Some("Statistics"),
+  prompt: None,
+  build: |_, _, cx| Ok(cx.new(StatsView::new).into()),
+  title: |_, _| "Statistics".into(),
+  detail: tab_kind::no_detail,
+  focus_handle: tab_kind::no_focus,
+  serialize: tab_kind::no_state,
+};
+
+/// 会话统计和诊断计数器面板
+pub struct StatsView {
+  _observe_stats: Subscription,
+  /// 诊断计数器是原子变量，没有变化通知，定时刷新
+  _refresh_metrics: Task<()>,
+}
+
+impl StatsView {
+  pub fn new(cx: &mut Context<Self>) -> Self {
+    let refresh_metrics = cx.spawn(async move |this, cx| {
+      loop {
+        cx.background_executor()
+          .timer(METRICS_REFRESH_INTERVAL)
+          .await;
+        if this.update(cx, |_, cx| cx.notify()).is_err() {
+          break;
+        }
+      }
+    });
+
+    Self {
+      _observe_stats: cx.observe_global::<SessionStats>(|_, cx| cx.notify()),
+      _refresh_metrics: refresh_metrics,
+    }
+  }
+
+  /// 选择保存位置并导出诊断计数器
+  fn export_metrics(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+    let directory = dirs::download_dir()
+      .or_else(dirs::home_dir)
+      .unwrap_or_else(std::env::temp_dir);
+    let name = format!("catus-metrics-{}.json", metrics::snapshot().exported_at);
+    let path = cx.prompt_for_new_path(&directory, Some(&name));
+    cx.spawn_in(window, async move |_, cx| {
+      let Ok(Ok(Some(path))) = path.await else {
+        return;
+      };
+      let result = metrics::export(&path);
+      let _ = cx.update(|window, cx| {
+        let notification = match result {
+          Ok(()) => Notification::success(format!("Exported metrics to {}", path.display())),
+          Err(e) => Notification::error(e),
+        };
+        window.push_notification(notification, cx);
+      });
+    })
+    .detach();
+  }
+
+  fn render_metrics(&self, cx: &mut Context<Self>) -> impl IntoElement {
+    let rows = Metric::ALL.iter().map(|metric| {
+      let value = match metric {
+        Metric::BytesParsed => format_bytes(metric.value()),
+        _ => metric.value().to_string(),
+      };
+      h_flex()
+        .gap_4()
+        .py_1()
+        .child(div().w(px(160.)).child(metric.label()))
+        .child(div().child(value))
+    });
+
+    v_flex()
+      .gap_2()
+      .child(
+        h_flex()
+          .gap_2()
+          .child(div().text_lg().font_semibold().child("Diagnostics"))
+          .child(div().flex_1())
+          .child(
+            Button::new("export-metrics")
+              .small()
+              .label("Export Metrics JSON…")
+              .on_click(cx.listener(|this, _, window, cx| this.export_metrics(window, cx))),
+          )
+          .child(
+            Button::new("reset-metrics")
+              .small()
+              .ghost()
+              .label("Reset")
+              .on_click(cx.listener(|_, _, _window, cx| {
+                metrics::reset();
+                cx.notify();
+              })),
+          ),
+      )
+      .child(
+        div()
+          .text_sm()
+          .text_color(cx.theme().muted_foreground)
+          .child(format!(
+            "Counted locally over the last {} s and never sent anywhere. Export them to attach to a bug report.",
+            metrics::uptime_secs()
+          )),
+      )
+      .child(v_flex().text_sm().children(rows))
+  }
+
+  /// 执行命令最多的主机或目录
+  fn render_top(title: &'static str, counts: Vec<(&str, u64)>, cx: &App) -> impl IntoElement {
+    let empty = counts.is_empty().then(|| {
+      div()
+        .text_color(cx.theme().muted_foreground)
+        .child("None yet")
+    });
+    let rows = counts.into_iter().map(|(name, count)| {
+      h_flex()
+        .gap_4()
+        .py_1()
+        .child(div().flex_1().min_w_0().truncate().child(name.to_string()))
+        .child(div().w(px(80.)).child(count.to_string()))
+    });
+    v_flex()
+      .flex_1()
+      .min_w_0()
+      .text_sm()
+      .child(
+        div()
+          .font_semibold()
+          .border_b_1()
+          .border_color(cx.theme().border)
+          .py_1()
+          .child(title),
+      )
+      .children(empty)
+      .children(rows)
+  }
+
+  fn render_row(
+    date: impl Into<SharedString>,
+    commands: impl Into<SharedString>,
+    output: impl Into<SharedString>,
+  ) -> Div {
+    h_flex()
+      .gap_4()
+      .py_1()
+      .child(div().w(px(120.)).child(date.into()))
+      .child(div().w(px(100.)).child(commands.into()))
+      .child(div().w(px(100.)).child(output.into()))
+  }
+}
+
+impl Render for StatsView {
+  fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    let stats = cx.global::<SessionStats>();
+    let enabled = stats.enabled;
+    let total = stats.total();
+    let rows: Vec<_> = stats
+      .days()
+      .map(|(day, day_stats)| {
+        Self::render_row(
+          format_day(day),
+          day_stats.commands.to_string(),
+          format_bytes(day_stats.output_bytes),
+        )
+      })
+      .collect();
+
+    v_flex()
+      .size_full()
+      .p_4()
+      .gap_3()
+      .child(
+        h_flex()
+          .gap_2()
+          .child(div().text_lg().font_semibold().child("Session Statistics"))
+          .child(div().flex_1())
+          .child(
+            Button::new("toggle-stats")
+              .small()
+              .label(if enabled {
+                "Stop Tracking"
+              } else {
+                "Start Tracking"
+              })
+              .on_click(move |_, _window, cx| {
+                cx.update_global::<SessionStats, _>(|stats, _cx| stats.set_enabled(!enabled));
+              }),
+          )
+          .child(
+            Button::new("clear-stats")
+              .small()
+              .ghost()
+              .label("Clear")
+              .on_click(|_, _window, cx| {
+                cx.update_global::<SessionStats, _>(|stats, _cx| stats.clear());
+              }),
+          ),
+      )
+      .child(
+        div()
+          .text_sm()
+          .text_color(cx.theme().muted_foreground)
+          .child(if enabled {
+            "Statistics are recorded locally and never leave this machine."
+          } else {
+            "Tracking is off. Statistics are only recorded after you opt in."
+          }),
+      )
+      .child(
+        v_flex()
+          .text_sm()
+          .child(
+            Self::render_row("Date (UTC)", "Commands", "Output")
+              .font_semibold()
+              .border_b_1()
+              .border_color(cx.theme().border),
+          )
+          .children(rows)
+          .child(
+            Self::render_row(
+              "Total",
+              total.commands.to_string(),
+              format_bytes(total.output_bytes),
+            )
+            .font_semibold()
+            .border_t_1()
+            .border_color(cx.theme().border),
+          ),
+      )
+      .child(
+        h_flex()
+          .gap_6()
+          .items_start()
+          .child(Self::render_top(
+            "Top Hosts",
+            session_stats::top(&total.hosts),
+            cx,
+          ))
+          .child(Self::render_top(
+            "Top Directories",
+            session_stats::top(&total.directories),
+            cx,
+          )),
+      )
+      .child(self.render_metrics(cx))
+  }
+}
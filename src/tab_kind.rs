@@ -0,0 +1,80 @@
+use gpui::{AnyView, App, FocusHandle, Global, SharedString, Task, Window};
+use gpui_component::IconName;
+use serde_json::Value;
+
+/// 向用户询问新 Tab 的初始状态，返回 None 表示取消
+pub type PromptState = fn(&mut Window, &mut App) -> Task<Option<Value>>;
+/// 根据状态创建 Tab 视图
+pub type BuildView = fn(Option<&Value>, &mut Window, &mut App) -> Result<AnyView, String>;
+
+/// Tab 类型描述
+///
+/// 新的 Tab 类型只需在所属模块的 `init` 中注册一个描述，
+/// Workspace、Tab 栏和会话恢复都通过描述操作视图，不需要为每种类型修改核心代码。
+pub struct TabKindDescriptor {
+  /// 唯一 ID，也是会话文件中的 `kind`
+  pub id: &'static str,
+  pub icon: IconName,
+  /// 新建菜单中的名称，None 表示不出现在菜单中
+  pub menu_label: Option<&'static str>,
+  /// 新建前向用户询问初始状态（如选择文件），返回 None 表示取消
+  pub prompt: Option<PromptState>,
+  /// 创建视图；`state` 为会话中保存的或 `prompt` 返回的状态，直接新建时为 None
+  pub build: BuildView,
+  /// 新建 Tab 的标题
+  pub title: fn(&AnyView, &App) -> SharedString,
+  /// 悬停提示中标题之后的附加信息
+  pub detail: fn(&AnyView, &App) -> Option<SharedString>,
+  /// 激活 Tab 时需要获得焦点的句柄
+  pub focus_handle: fn(&AnyView, &App) -> Option<FocusHandle>,
+  /// 序列化视图状态，用于会话恢复
+  pub serialize: fn(&AnyView, &App) -> Value,
+}
+
+/// 已注册的 Tab 类型，按注册顺序排列（也是新建菜单中的顺序）
+#[derive(Default)]
+pub struct TabKinds {
+  kinds: Vec<&'static TabKindDescriptor>,
+}
+
+impl Global for TabKinds {}
+
+impl TabKinds {
+  /// 注册 Tab 类型，ID 重复时替换已有的描述
+  pub fn register(kind: &'static TabKindDescriptor, cx: &mut App) {
+    let kinds = &mut cx.default_global::<Self>().kinds;
+    match kinds.iter_mut().find(|existing| existing.id == kind.id) {
+      Some(existing) => *existing = kind,
+      None => kinds.push(kind),
+    }
+  }
+
+  pub fn get(id: &str, cx: &App) -> Option<&'static TabKindDescriptor> {
+    cx.try_global::<Self>()?
+      .kinds
+      .iter()
+      .find(|kind| kind.id == id)
+      .copied()
+  }
+
+  pub fn all(cx: &App) -> Vec<&'static TabKindDescriptor> {
+    cx.try_global::<Self>()
+      .map(|kinds| kinds.kinds.clone())
+      .unwrap_or_default()
+  }
+}
+
+/// 没有附加信息的 `detail`
+pub fn no_detail(_: &AnyView, _: &App) -> Option<SharedString> {
+  None
+}
+
+/// 不需要焦点的 `focus_handle`
+pub fn no_focus(_: &AnyView, _: &App) -> Option<FocusHandle> {
+  None
+}
+
+/// 没有状态的 `serialize`
+pub fn no_state(_: &AnyView, _: &App) -> Value {
+  Value::Null
+}
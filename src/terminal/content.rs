@@ -1,10 +1,13 @@
 use alacritty_terminal::{
   index::{Column, Line},
-  term::{RenderableCursor, TermMode, cell::Cell},
-  vte::ansi::Color as AnsiColor,
+  term::{RenderableCursor, TermMode, cell::Cell, color::Colors},
+  vte::ansi::{Color as AnsiColor, NamedColor},
 };
 use gpui::*;
 
+use crate::terminal::encoding::DecodeStatus;
+use crate::terminal::highlight::HighlightMarks;
+
 /// 终端事件
 #[derive(Clone, Debug)]
 pub enum TerminalEvent {
@@ -16,7 +19,7 @@ pub enum TerminalEvent {
   Closed,
 }
 
-/// 带位置的单元格
+/// 带位置的单元格（行号为视口行号，0 为视口第一行）
 #[derive(Clone, Debug)]
 pub struct IndexedCell {
   pub point: TerminalPoint,
@@ -30,11 +33,37 @@ pub struct TerminalPoint {
   pub column: Column,
 }
 
-/// 选区范围
+/// 选区范围（行号为视口行号，可能超出视口）
 #[derive(Clone, Debug)]
 pub struct SelectionRange {
   pub start: TerminalPoint,
   pub end: TerminalPoint,
+  /// 块选区只包含起止列之间的部分
+  pub is_block: bool,
+}
+
+impl SelectionRange {
+  /// 选区在视口第 `row` 行覆盖的列范围（含两端）
+  pub fn columns_in_row(&self, row: i32, num_columns: usize) -> Option<(usize, usize)> {
+    if row < self.start.line.0 || row > self.end.line.0 {
+      return None;
+    }
+    let last_column = num_columns.saturating_sub(1);
+    if self.is_block {
+      return Some((self.start.column.0, self.end.column.0.min(last_column)));
+    }
+    let first = if row == self.start.line.0 {
+      self.start.column.0
+    } else {
+      0
+    };
+    let last = if row == self.end.line.0 {
+      self.end.column.0.min(last_column)
+    } else {
+      last_column
+    };
+    Some((first, last))
+  }
 }
 
 /// 选区类型
@@ -50,6 +79,17 @@ pub enum SelectionType {
   Lines,
 }
 
+impl From<SelectionType> for alacritty_terminal::selection::SelectionType {
+  fn from(ty: SelectionType) -> Self {
+    match ty {
+      SelectionType::Simple => Self::Simple,
+      SelectionType::Block => Self::Block,
+      SelectionType::Semantic => Self::Semantic,
+      SelectionType::Lines => Self::Lines,
+    }
+  }
+}
+
 /// 终端边界信息
 #[derive(Clone, Copy, Debug)]
 pub struct TerminalBounds {
@@ -116,6 +156,22 @@ pub struct TerminalContent {
   pub terminal_bounds: TerminalBounds,
   pub scrolled_to_top: bool,
   pub scrolled_to_bottom: bool,
+  /// 滚动历史行数
+  pub history_size: usize,
+  /// 高亮规则在滚动历史中的匹配标记
+  pub highlight_marks: HighlightMarks,
+  /// 当前跳转到的高亮匹配在视口中的行
+  pub active_highlight_row: Option<usize>,
+  /// 应用动态设置的颜色（OSC 4/10/11/12），未设置的项使用默认调色板
+  pub colors: Colors,
+  /// PTY 输出的解码状态
+  pub decode_status: DecodeStatus,
+  /// 全屏应用在网格之外定位光标时的最大行、列
+  pub grid_overflow: Option<(usize, usize)>,
+  /// 最近的输出像是二进制数据
+  pub binary_flood: bool,
+  /// 向上滚动后到达、还在视口下方的新输出行数
+  pub unseen_lines: usize,
   pub title: String,
 }
 
@@ -132,6 +188,14 @@ impl TerminalContent {
       terminal_bounds: TerminalBounds::new(px(8.), px(16.), Bounds::default(), 24, 80),
       scrolled_to_top: true,
       scrolled_to_bottom: true,
+      history_size: 0,
+      highlight_marks: HighlightMarks::default(),
+      active_highlight_row: None,
+      colors: Colors::default(),
+      decode_status: DecodeStatus::default(),
+      grid_overflow: None,
+      binary_flood: false,
+      unseen_lines: 0,
       title: "Terminal".to_string(),
     }
   }
@@ -148,6 +212,32 @@ impl TerminalContent {
     self.cursor_char = cursor_char;
   }
 
+  /// 解析颜色，优先使用应用通过 OSC 4/10/11/12 动态设置的颜色
+  pub fn resolve_color(&self, color: &AnsiColor) -> [u8; 3] {
+    match color {
+      AnsiColor::Named(name) => self.palette_rgb(*name as usize),
+      AnsiColor::Spec(rgb) => [rgb.r, rgb.g, rgb.b],
+      AnsiColor::Indexed(idx) => self.palette_rgb(*idx as usize),
+    }
+  }
+
+  /// 调色板中索引对应的当前颜色
+  pub fn palette_rgb(&self, index: usize) -> [u8; 3] {
+    self.colors[index]
+      .map(|rgb| [rgb.r, rgb.g, rgb.b])
+      .unwrap_or_else(|| default_palette_rgb(index))
+  }
+
+  /// 当前背景色
+  pub fn background_rgb(&self) -> [u8; 3] {
+    self.palette_rgb(NamedColor::Background as usize)
+  }
+
+  /// 应用设置的光标颜色（OSC 12），未设置时返回 None
+  pub fn cursor_rgb(&self) -> Option<[u8; 3]> {
+    self.colors[NamedColor::Cursor as usize].map(|rgb| [rgb.r, rgb.g, rgb.b])
+  }
+
   /// 设置终端标题
   pub fn set_title(&mut self, title: String) {
     self.title = title;
@@ -167,58 +257,54 @@ impl Default for TerminalContent {
 
 impl EventEmitter<TerminalEvent> for TerminalContent {}
 
-/// 将 alacritty 的 RenderableCursor 转换为 CursorState
-pub fn renderable_cursor_to_state(cursor: &RenderableCursor) -> CursorState {
+/// 将 alacritty 的 RenderableCursor 转换为 CursorState（行号转换为视口行号）
+pub fn renderable_cursor_to_state(cursor: &RenderableCursor, display_offset: usize) -> CursorState {
   CursorState {
     point: TerminalPoint {
-      line: cursor.point.line,
+      line: cursor.point.line + display_offset,
       column: cursor.point.column,
     },
     shape: cursor.shape,
   }
 }
 
-/// 将 ANSI 颜色转换为 RGB
-pub fn ansi_color_to_rgb(color: &AnsiColor) -> [u8; 3] {
-  use alacritty_terminal::vte::ansi::NamedColor;
-
-  match color {
-    AnsiColor::Named(name) => match name {
-      NamedColor::Black => [0, 0, 0],
-      NamedColor::Red => [255, 0, 0],
-      NamedColor::Green => [0, 255, 0],
-      NamedColor::Yellow => [255, 255, 0],
-      NamedColor::Blue => [0, 0, 255],
-      NamedColor::Magenta => [255, 0, 255],
-      NamedColor::Cyan => [0, 255, 255],
-      NamedColor::White => [255, 255, 255],
-      NamedColor::BrightBlack => [64, 64, 64],
-      NamedColor::BrightRed => [255, 64, 64],
-      NamedColor::BrightGreen => [64, 255, 64],
-      NamedColor::BrightYellow => [255, 255, 64],
-      NamedColor::BrightBlue => [64, 64, 255],
-      NamedColor::BrightMagenta => [255, 64, 255],
-      NamedColor::BrightCyan => [64, 255, 255],
-      NamedColor::BrightWhite => [255, 255, 255],
-      NamedColor::Foreground => [212, 212, 212],
-      NamedColor::Background => [30, 30, 30],
-      _ => [212, 212, 212],
-    },
-    AnsiColor::Spec(rgb) => [rgb.r, rgb.g, rgb.b],
-    AnsiColor::Indexed(idx) => {
-      // ANSI 256 色表简化处理
-      match idx {
-        0 => [0, 0, 0],
-        1 => [255, 0, 0],
-        2 => [0, 255, 0],
-        3 => [255, 255, 0],
-        4 => [0, 0, 255],
-        5 => [255, 0, 255],
-        6 => [0, 255, 255],
-        7 => [255, 255, 255],
-        _ => [212, 212, 212],
-      }
+/// 默认调色板中的颜色
+///
+/// 索引与 alacritty 的颜色表一致：0-15 为 ANSI 基本色，16-231 为 6×6×6 色立方，
+/// 232-255 为灰阶，256 起为前景、背景、光标等具名颜色
+pub fn default_palette_rgb(index: usize) -> [u8; 3] {
+  const ANSI: [[u8; 3]; 16] = [
+    [0, 0, 0],
+    [255, 0, 0],
+    [0, 255, 0],
+    [255, 255, 0],
+    [0, 0, 255],
+    [255, 0, 255],
+    [0, 255, 255],
+    [255, 255, 255],
+    [64, 64, 64],
+    [255, 64, 64],
+    [64, 255, 64],
+    [255, 255, 64],
+    [64, 64, 255],
+    [255, 64, 255],
+    [64, 255, 255],
+    [255, 255, 255],
+  ];
+
+  match index {
+    0..=15 => ANSI[index],
+    16..=231 => {
+      let index = index - 16;
+      let level = |v: usize| if v == 0 { 0 } else { (v * 40 + 55) as u8 };
+      [level(index / 36), level(index / 6 % 6), level(index % 6)]
+    }
+    232..=255 => {
+      let gray = ((index - 232) * 10 + 8) as u8;
+      [gray, gray, gray]
     }
+    _ if index == NamedColor::Background as usize => [30, 30, 30],
+    _ => [212, 212, 212],
   }
 }
 
@@ -0,0 +1,248 @@
+use encoding_rs::{DecoderResult, Encoding, GBK, UTF_8, WINDOWS_1252};
+use std::borrow::Cow;
+use std::fmt;
+
+/// 终端字符编码
+///
+/// 终端内部始终使用 UTF-8，其他编码在 PTY 输出进入解析器之前转码为 UTF-8，
+/// 用户输入在写入 PTY 之前转码回原编码
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TerminalEncoding {
+  #[default]
+  Utf8,
+  Gbk,
+  Latin1,
+}
+
+impl TerminalEncoding {
+  pub const ALL: [Self; 3] = [Self::Utf8, Self::Gbk, Self::Latin1];
+
+  pub fn label(self) -> &'static str {
+    match self {
+      Self::Utf8 => "UTF-8",
+      Self::Gbk => "GBK",
+      Self::Latin1 => "Latin-1",
+    }
+  }
+
+  fn encoding(self) -> &'static Encoding {
+    match self {
+      Self::Utf8 => UTF_8,
+      Self::Gbk => GBK,
+      // 按 WHATWG 规范，Latin-1 即 windows-1252
+      Self::Latin1 => WINDOWS_1252,
+    }
+  }
+
+  /// 将用户输入（UTF-8）转码为终端编码；无法表示的字符会被替换
+  pub fn encode<'a>(self, data: &'a [u8]) -> Cow<'a, [u8]> {
+    if self == Self::Utf8 {
+      return Cow::Borrowed(data);
+    }
+    let text = String::from_utf8_lossy(data);
+    let (bytes, _, _) = self.encoding().encode(&text);
+    Cow::Owned(bytes.into_owned())
+  }
+}
+
+impl fmt::Display for TerminalEncoding {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self.label())
+  }
+}
+
+/// 解码状态，随 TerminalContent 一起发送给 UI
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DecodeStatus {
+  pub encoding: TerminalEncoding,
+  /// 被替换为 U+FFFD 的无效字节序列数
+  pub replaced: usize,
+  /// 根据无效字节序列推测的编码
+  pub suggestion: Option<TerminalEncoding>,
+}
+
+/// PTY 输出解码器（后台任务中使用）
+///
+/// 使用流式解码，跨读取块的多字节字符可以正确拼接
+pub struct OutputDecoder {
+  decoder: encoding_rs::Decoder,
+  status: DecodeStatus,
+  /// UTF-8 模式下同时按 GBK 流式解码，用于推测编码；跨块的 GBK 字符同样能正确拼接。给出推测后不再运行
+  probe: Option<encoding_rs::Decoder>,
+}
+
+impl OutputDecoder {
+  pub fn new(encoding: TerminalEncoding) -> Self {
+    Self {
+      decoder: encoding.encoding().new_decoder_without_bom_handling(),
+      status: DecodeStatus {
+        encoding,
+        ..Default::default()
+      },
+      probe: (encoding == TerminalEncoding::Utf8).then(|| GBK.new_decoder_without_bom_handling()),
+    }
+  }
+
+  pub fn status(&self) -> DecodeStatus {
+    self.status
+  }
+
+  /// 切换编码并清空统计
+  pub fn set_encoding(&mut self, encoding: TerminalEncoding) {
+    *self = Self::new(encoding);
+  }
+
+  /// 将 PTY 输出解码为 UTF-8，无效字节序列替换为 U+FFFD 并计数
+  pub fn decode(&mut self, data: &[u8]) -> String {
+    let mut output = String::with_capacity(
+      self
+        .decoder
+        .max_utf8_buffer_length_without_replacement(data.len())
+        .unwrap_or(data.len() * 3),
+    );
+    let mut input = data;
+    let mut replaced = 0;
+    loop {
+      let (result, read) =
+        self
+          .decoder
+          .decode_to_string_without_replacement(input, &mut output, false);
+      input = &input[read..];
+      match result {
+        DecoderResult::InputEmpty => break,
+        DecoderResult::OutputFull => output.reserve(input.len() * 3 + 4),
+        DecoderResult::Malformed(_, _) => {
+          replaced += 1;
+          output.push('\u{FFFD}');
+        }
+      }
+    }
+
+    // 推测非 UTF-8 数据的编码：这块数据按 GBK 解码没有错误时视为 GBK，否则按 Latin-1 处理
+    let probe_malformed = self.probe(data);
+    if replaced > 0 {
+      self.status.replaced += replaced;
+      if self.probe.take().is_some() {
+        self.status.suggestion = Some(if probe_malformed == 0 {
+          TerminalEncoding::Gbk
+        } else {
+          TerminalEncoding::Latin1
+        });
+      }
+    }
+    output
+  }
+
+  /// 按 GBK 流式解码，返回这块数据中的无效字节序列数；不在推测编码时返回 0
+  fn probe(&mut self, data: &[u8]) -> usize {
+    let Some(probe) = &mut self.probe else {
+      return 0;
+    };
+    let mut buffer = [0u8; 4096];
+    let mut input = data;
+    let mut malformed = 0;
+    loop {
+      let (result, read, _) = probe.decode_to_utf8_without_replacement(input, &mut buffer, false);
+      input = &input[read..];
+      match result {
+        DecoderResult::InputEmpty => break,
+        DecoderResult::OutputFull => {}
+        DecoderResult::Malformed(_, _) => malformed += 1,
+      }
+    }
+    malformed
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn decode(chunks: &[&[u8]]) -> (String, DecodeStatus) {
+    let mut decoder = OutputDecoder::new(TerminalEncoding::Utf8);
+    let text = chunks.iter().map(|chunk| decoder.decode(chunk)).collect();
+    (text, decoder.status())
+  }
+
+  #[test]
+  fn utf8_split_across_chunks_is_not_invalid() {
+    let whole = "你好，世界".as_bytes();
+    for split in 1..whole.len() {
+      let (first, second) = whole.split_at(split);
+      let (text, status) = decode(&[first, second]);
+      assert_eq!(text, "你好，世界");
+      assert_eq!(status.replaced, 0);
+      assert_eq!(status.suggestion, None);
+    }
+  }
+
+  #[test]
+  fn suggests_gbk() {
+    let (gbk, _, _) = GBK.encode("编译完成");
+    let (_, status) = decode(&[&gbk]);
+    assert!(status.replaced > 0);
+    assert_eq!(status.suggestion, Some(TerminalEncoding::Gbk));
+  }
+
+  #[test]
+  fn suggests_gbk_when_split_across_chunks() {
+    let (gbk, _, _) = GBK.encode("编译完成，没有错误");
+    for split in 1..gbk.len() {
+      let (first, second) = gbk.split_at(split);
+      let (_, status) = decode(&[b"ok\r\n", first, second]);
+      assert_eq!(
+        status.suggestion,
+        Some(TerminalEncoding::Gbk),
+        "split at {}",
+        split
+      );
+    }
+  }
+
+  #[test]
+  fn suggests_latin1() {
+    // "café" 在 Latin-1 中 é 为 0xE9，后面跟 ASCII，不是有效的 GBK 双字节字符
+    let (_, status) = decode(&[b"caf\xe9 ok\xff"]);
+    assert_eq!(status.suggestion, Some(TerminalEncoding::Latin1));
+  }
+
+  #[test]
+  fn keeps_the_first_suggestion() {
+    let (gbk, _, _) = GBK.encode("中文");
+    let (_, status) = decode(&[&gbk, b"caf\xe9 ok\xff"]);
+    assert_eq!(status.suggestion, Some(TerminalEncoding::Gbk));
+  }
+
+  #[test]
+  fn other_encodings_do_not_suggest() {
+    let mut decoder = OutputDecoder::new(TerminalEncoding::Gbk);
+    assert_eq!(decoder.decode(&GBK.encode("中文").0), "中文");
+    decoder.decode(b"\xff");
+    assert_eq!(decoder.status().suggestion, None);
+  }
+
+  #[test]
+  fn gbk_split_across_chunks_decodes() {
+    let (gbk, _, _) = GBK.encode("中文");
+    let mut decoder = OutputDecoder::new(TerminalEncoding::Gbk);
+    let text = decoder.decode(&gbk[..1]) + &decoder.decode(&gbk[1..]);
+    assert_eq!(text, "中文");
+    assert_eq!(decoder.status().replaced, 0);
+  }
+
+  #[test]
+  fn encode_input() {
+    assert_eq!(
+      TerminalEncoding::Utf8.encode("中".as_bytes()).as_ref(),
+      "中".as_bytes()
+    );
+    assert_eq!(
+      TerminalEncoding::Gbk.encode("中".as_bytes()).as_ref(),
+      b"\xd6\xd0"
+    );
+    assert_eq!(
+      TerminalEncoding::Latin1.encode("é".as_bytes()).as_ref(),
+      b"\xe9"
+    );
+  }
+}
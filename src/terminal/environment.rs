@@ -0,0 +1,94 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use gpui::*;
+use gpui_component::notification::Notification;
+use gpui_component::{ActiveTheme as _, WindowExt as _, h_flex, v_flex};
+use serde_json::Value;
+use tokio::sync::oneshot;
+
+use crate::settings::Settings;
+use crate::terminal::TerminalOptions;
+use crate::terminal::content::rgb_to_hsla;
+
+/// 没有设置横幅颜色时环境标记的颜色
+pub const DEFAULT_COLOR: [u8; 3] = [0x3c, 0x3c, 0x3c];
+
+/// 环境的颜色，环境已从设置中删除时为 None
+pub fn color(name: &str, cx: &App) -> Option<[u8; 3]> {
+  let set = cx.global::<Settings>().environment(name)?;
+  Some(set.color.unwrap_or(DEFAULT_COLOR))
+}
+
+/// 让用户选择环境变量集，返回新终端的 `TerminalOptions` 状态；取消时返回 None
+pub fn prompt_environment(window: &mut Window, cx: &mut App) -> Task<Option<Value>> {
+  let environments = cx.global::<Settings>().environments.clone();
+  if environments.is_empty() {
+    window.push_notification(
+      Notification::info("No environments yet. Add them to \"environments\" in settings.json."),
+      cx,
+    );
+    return Task::ready(None);
+  }
+
+  let (reply_tx, reply_rx) = oneshot::channel::<Option<String>>();
+  // 选择和关闭都会回复，只有第一次有效
+  let reply = Rc::new(Cell::new(Some(reply_tx)));
+
+  window.open_dialog(cx, move |dialog, _window, cx| {
+    let rows = environments.iter().enumerate().map(|(index, set)| {
+      let name = set.name.clone();
+      let reply = reply.clone();
+      h_flex()
+        .id(("environment", index))
+        .gap_2()
+        .px_2()
+        .py_1()
+        .rounded_md()
+        .cursor_pointer()
+        .hover(|style| style.bg(cx.theme().secondary_hover))
+        .child(
+          div()
+            .size(px(10.))
+            .rounded_full()
+            .bg(rgb_to_hsla(set.color.unwrap_or(DEFAULT_COLOR))),
+        )
+        .child(div().flex_1().child(set.name.clone()))
+        .child(
+          div()
+            .text_xs()
+            .text_color(cx.theme().muted_foreground)
+            .child(match set.vars.len() {
+              1 => "1 variable".to_string(),
+              count => format!("{} variables", count),
+            }),
+        )
+        .on_click(move |_, window, cx| {
+          if let Some(reply) = reply.take() {
+            let _ = reply.send(Some(name.clone()));
+          }
+          window.close_dialog(cx);
+        })
+    });
+
+    let reply = reply.clone();
+    dialog
+      .title("Open Terminal in Environment")
+      .w(px(360.))
+      .child(v_flex().gap_1().children(rows))
+      .on_close(move |_, _window, _cx| {
+        if let Some(reply) = reply.take() {
+          let _ = reply.send(None);
+        }
+      })
+  });
+
+  cx.foreground_executor().spawn(async move {
+    let name = reply_rx.await.ok().flatten()?;
+    let options = TerminalOptions {
+      environment: Some(name),
+      ..TerminalOptions::default()
+    };
+    serde_json::to_value(options).ok()
+  })
+}
@@ -0,0 +1,43 @@
+/// 每累计这么多字节判断一次
+const WINDOW_BYTES: usize = 4096;
+/// 可疑字节占比超过该值时认为是二进制输出
+const SUSPICIOUS_RATIO: f32 = 0.1;
+
+/// 检测误 `cat` 二进制文件时的乱码输出
+///
+/// 正常的终端输出（包括转义序列）几乎只包含可打印字符和少数控制字符；
+/// 二进制数据中 NUL、BEL 等控制字节和无效的字节序列占比明显偏高
+#[derive(Default)]
+pub struct BinaryFloodDetector {
+  window_bytes: usize,
+  suspicious: usize,
+  flooding: bool,
+}
+
+impl BinaryFloodDetector {
+  /// 最近一个窗口是否像二进制输出
+  pub fn is_flooding(&self) -> bool {
+    self.flooding
+  }
+
+  pub fn reset(&mut self) {
+    *self = Self::default();
+  }
+
+  /// 统计一块输出；`invalid` 为该块解码时替换的无效字节序列数
+  pub fn scan(&mut self, data: &[u8], invalid: usize) {
+    self.window_bytes += data.len();
+    self.suspicious += invalid + data.iter().filter(|byte| is_suspicious(**byte)).count();
+
+    if self.window_bytes >= WINDOW_BYTES {
+      self.flooding = self.suspicious as f32 / self.window_bytes as f32 > SUSPICIOUS_RATIO;
+      self.window_bytes = 0;
+      self.suspicious = 0;
+    }
+  }
+}
+
+/// 正常输出中很少出现的控制字节（换行、制表、退格、转义等除外）
+fn is_suspicious(byte: u8) -> bool {
+  matches!(byte, 0x00..=0x07 | 0x0b | 0x0c | 0x0e..=0x1a | 0x1c..=0x1f | 0x7f)
+}
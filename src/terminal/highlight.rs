@@ -0,0 +1,220 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use alacritty_terminal::event::EventListener;
+use alacritty_terminal::grid::{Dimensions, Scroll};
+use alacritty_terminal::index::{Column, Direction, Line, Point, Side};
+use alacritty_terminal::term::Term;
+use alacritty_terminal::term::search::{RegexIter, RegexSearch};
+
+/// 两次全量扫描之间的最小间隔，避免大量输出时反复扫描滚动历史
+const SCAN_INTERVAL: Duration = Duration::from_millis(250);
+
+/// 高亮规则：匹配正则的行会在滚动条上标记，并可以在匹配之间跳转
+#[derive(Clone, Debug)]
+pub struct HighlightRule {
+  pub name: String,
+  pub pattern: String,
+  pub color: [u8; 3],
+}
+
+impl HighlightRule {
+  pub fn new(name: impl Into<String>, pattern: impl Into<String>, color: [u8; 3]) -> Self {
+    Self {
+      name: name.into(),
+      pattern: pattern.into(),
+      color,
+    }
+  }
+
+  /// 默认规则：错误与警告
+  pub fn defaults() -> Vec<Self> {
+    vec![
+      Self::new(
+        "Error",
+        r"\b(ERROR|Error|error|FATAL|FAILED)\b",
+        [241, 76, 76],
+      ),
+      Self::new(
+        "Warning",
+        r"\b(WARN|WARNING|Warning|warning)\b",
+        [229, 192, 123],
+      ),
+    ]
+  }
+}
+
+/// 滚动条上的匹配标记
+#[derive(Clone, Copy, Debug)]
+pub struct HighlightMark {
+  /// 自滚动历史顶部起的行号
+  pub row: usize,
+  pub color: [u8; 3],
+}
+
+/// 匹配标记快照，随 TerminalContent 一起发送给 UI
+#[derive(Clone, Debug, Default)]
+pub struct HighlightMarks {
+  pub marks: Arc<Vec<HighlightMark>>,
+  /// 扫描时的总行数（滚动历史 + 屏幕）
+  pub total_lines: usize,
+}
+
+/// 高亮扫描器（后台任务中使用）
+pub struct HighlightScanner {
+  searches: Vec<(RegexSearch, [u8; 3])>,
+  marks: HighlightMarks,
+  last_scan: Option<Instant>,
+  /// 有被节流跳过的扫描，需要在间隔结束后补上
+  pending: bool,
+  /// 上一次跳转到的匹配所在行，自滚动历史顶部起计（与 `HighlightMark::row` 相同），
+  /// 新的输出滚入历史后仍指向同一行
+  active_row: Option<usize>,
+}
+
+impl HighlightScanner {
+  pub fn new(rules: &[HighlightRule]) -> Self {
+    let searches = rules
+      .iter()
+      .filter_map(|rule| match RegexSearch::new(&rule.pattern) {
+        Ok(search) => Some((search, rule.color)),
+        Err(e) => {
+          eprintln!("Invalid highlight rule {}: {}", rule.name, e);
+          None
+        }
+      })
+      .collect();
+
+    Self {
+      searches,
+      marks: HighlightMarks::default(),
+      last_scan: None,
+      pending: false,
+      active_row: None,
+    }
+  }
+
+  /// 当前的匹配标记
+  pub fn marks(&self) -> &HighlightMarks {
+    &self.marks
+  }
+
+  /// 上一次跳转到的匹配在视口中的行
+  pub fn active_row<T>(&self, term: &Term<T>) -> Option<usize> {
+    let line = self.active_line(term)?.0 + term.grid().display_offset() as i32;
+    (0..term.screen_lines() as i32)
+      .contains(&line)
+      .then_some(line as usize)
+  }
+
+  /// 上一次跳转到的匹配的网格行
+  fn active_line<T>(&self, term: &Term<T>) -> Option<Line> {
+    Some(Line(self.active_row? as i32 - term.history_size() as i32))
+  }
+
+  /// 被节流跳过的扫描还要等多久才能进行；没有被跳过的扫描时返回 None
+  pub fn pending_delay(&self) -> Option<Duration> {
+    if !self.pending {
+      return None;
+    }
+    Some(self.last_scan.map_or(Duration::ZERO, |at| {
+      SCAN_INTERVAL.saturating_sub(at.elapsed())
+    }))
+  }
+
+  /// 重新扫描整个滚动历史；非强制扫描会按 `SCAN_INTERVAL` 节流，
+  /// 被跳过时记下，由调用方在 `pending_delay` 之后再次调用
+  pub fn refresh<T>(&mut self, term: &Term<T>, force: bool) {
+    if self.searches.is_empty() {
+      return;
+    }
+    if !force
+      && self
+        .last_scan
+        .is_some_and(|at| at.elapsed() < SCAN_INTERVAL)
+    {
+      self.pending = true;
+      return;
+    }
+    self.last_scan = Some(Instant::now());
+    self.pending = false;
+
+    let start = Point::new(term.topmost_line(), Column(0));
+    let end = Point::new(term.bottommost_line(), term.last_column());
+    let history_size = term.history_size() as i32;
+
+    let mut marks = Vec::new();
+    for (search, color) in &mut self.searches {
+      let mut last_line = None;
+      for found in RegexIter::new(start, end, Direction::Right, term, search) {
+        let line = found.start().line;
+        if last_line != Some(line) {
+          last_line = Some(line);
+          marks.push(HighlightMark {
+            row: (line.0 + history_size) as usize,
+            color: *color,
+          });
+        }
+      }
+    }
+    marks.sort_by_key(|mark| mark.row);
+
+    self.marks = HighlightMarks {
+      marks: Arc::new(marks),
+      total_lines: term.total_lines(),
+    };
+  }
+
+  /// 跳转到下一个（`Direction::Right`）或上一个（`Direction::Left`）匹配行，
+  /// 并滚动使其显示在视口顶部。到达末尾时循环。
+  pub fn jump<T: EventListener>(&mut self, term: &mut Term<T>, direction: Direction) {
+    let display_offset = term.grid().display_offset() as i32;
+    let viewport_top = Line(-display_offset);
+    let viewport_bottom = viewport_top + (term.screen_lines() as i32 - 1);
+
+    // 上次的匹配仍在视口中时从它继续，否则从视口顶部开始
+    let current = self
+      .active_line(term)
+      .filter(|line| (viewport_top..=viewport_bottom).contains(line))
+      .unwrap_or(viewport_top);
+
+    let origin = match direction {
+      Direction::Right if current < term.bottommost_line() => Point::new(current + 1, Column(0)),
+      Direction::Right => Point::new(term.topmost_line(), Column(0)),
+      Direction::Left if current > term.topmost_line() => {
+        Point::new(current - 1, term.last_column())
+      }
+      Direction::Left => Point::new(term.bottommost_line(), term.last_column()),
+    };
+
+    // 在所有规则中找离起点最近的匹配
+    let mut best: Option<Line> = None;
+    for (search, _) in &mut self.searches {
+      let Some(found) = term.search_next(search, origin, direction, Side::Left, None) else {
+        continue;
+      };
+      let line = found.start().line;
+      let distance = |line: Line| match direction {
+        Direction::Right if line >= origin.line => line.0 - origin.line.0,
+        Direction::Right => line.0 - origin.line.0 + term.total_lines() as i32,
+        Direction::Left if line <= origin.line => origin.line.0 - line.0,
+        Direction::Left => origin.line.0 - line.0 + term.total_lines() as i32,
+      };
+      if best.is_none_or(|best| distance(line) < distance(best)) {
+        best = Some(line);
+      }
+    }
+
+    if let Some(line) = best {
+      self.reveal(term, line);
+    }
+  }
+
+  /// 将某一行标记为当前匹配，并滚动使其显示在视口顶部
+  pub fn reveal<T: EventListener>(&mut self, term: &mut Term<T>, line: Line) {
+    self.active_row = Some((line.0 + term.history_size() as i32) as usize);
+    let display_offset = term.grid().display_offset() as i32;
+    let target_offset = (-line.0).clamp(0, term.history_size() as i32);
+    term.scroll_display(Scroll::Delta(target_offset - display_offset));
+  }
+}
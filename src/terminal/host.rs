@@ -0,0 +1,222 @@
+use gpui::App;
+
+use crate::settings::Settings;
+
+/// 带参数的 ssh 选项（参数可以紧跟在选项后，也可以是下一个词）
+const SSH_OPTIONS_WITH_ARGUMENT: &str = "BbcDEeFIiJLlmOopQRSWw";
+
+/// 从输入的命令行中识别 ssh 的目标主机，如 `ssh -p 2222 deploy@prod` 返回 `prod`
+pub fn ssh_destination(line: &str) -> Option<String> {
+  ssh_args_destination(line.split_whitespace())
+}
+
+/// 从进程的命令行参数中识别 ssh 的目标主机，第一个参数是程序名
+pub fn ssh_command_destination(command: &[String]) -> Option<String> {
+  ssh_args_destination(command.iter().map(String::as_str))
+}
+
+fn ssh_args_destination<'a>(mut words: impl Iterator<Item = &'a str>) -> Option<String> {
+  let program = words.next()?;
+  if program.rsplit('/').next() != Some("ssh") {
+    return None;
+  }
+  while let Some(word) = words.next() {
+    if let Some(option) = word.strip_prefix('-') {
+      // 选项组如 `-tp2222`：遇到带参数的选项后，余下部分或下一个词是参数
+      for (index, flag) in option.char_indices() {
+        if SSH_OPTIONS_WITH_ARGUMENT.contains(flag) {
+          if index + flag.len_utf8() == option.len() {
+            words.next();
+          }
+          break;
+        }
+      }
+      continue;
+    }
+    let destination = word.strip_prefix("ssh://").unwrap_or(word);
+    let host = destination.rsplit('@').next()?;
+    let host = match host.strip_prefix('[') {
+      // `[::1]:22` 形式的 IPv6 地址
+      Some(bracketed) => bracketed.split(']').next()?,
+      None if host.matches(':').count() == 1 => host.split(':').next()?,
+      None => host,
+    };
+    return (!host.is_empty()).then(|| host.to_ascii_lowercase());
+  }
+  None
+}
+
+/// 输出中是否有 ssh 客户端自己打印的断开或连接失败消息
+///
+/// 只匹配行首的完整消息，远程命令输出的 `Connection refused` 等文本不算断开
+pub fn is_disconnect(text: &str) -> bool {
+  text
+    .split('\n')
+    .any(|line| is_disconnect_line(line.trim_start_matches('\r').trim_end()))
+}
+
+fn is_disconnect_line(line: &str) -> bool {
+  // `Connection to prod closed.`、`Connection to prod closed by remote host.`
+  if let Some(rest) = line.strip_prefix("Connection to ") {
+    return rest.split_once(' ').is_some_and(|(host, tail)| {
+      !host.is_empty() && (tail == "closed." || tail == "closed by remote host.")
+    });
+  }
+  // `Connection closed by 10.0.0.1 port 22`
+  if let Some(rest) = line.strip_prefix("Connection closed by ") {
+    return rest.contains(" port ");
+  }
+  // `ssh: connect to host prod port 22: Connection refused`、`ssh: Could not resolve hostname prod: …`
+  if let Some(rest) = line.strip_prefix("ssh: ") {
+    return rest.starts_with("connect to host ") || rest.starts_with("Could not resolve hostname ");
+  }
+  false
+}
+
+/// 进程的命令行参数；只支持 Linux（读取 `/proc`），其他平台返回 None
+pub fn command_line(pid: u32) -> Option<Vec<String>> {
+  #[cfg(target_os = "linux")]
+  {
+    let data = std::fs::read(format!("/proc/{}/cmdline", pid)).ok()?;
+    let args: Vec<String> = data
+      .split(|&byte| byte == 0)
+      .filter(|arg| !arg.is_empty())
+      .map(|arg| String::from_utf8_lossy(arg).into_owned())
+      .collect();
+    (!args.is_empty()).then_some(args)
+  }
+  #[cfg(not(target_os = "linux"))]
+  {
+    let _ = pid;
+    None
+  }
+}
+
+/// 主机名是否匹配模式，模式中的 `*` 匹配任意字符，不区分大小写
+pub fn matches(pattern: &str, host: &str) -> bool {
+  let pattern = pattern.to_ascii_lowercase();
+  let host = host.to_ascii_lowercase();
+  let mut parts = pattern.split('*');
+  let first = parts.next().unwrap_or_default();
+  let Some(mut rest) = host.strip_prefix(first) else {
+    return false;
+  };
+  let parts: Vec<&str> = parts.collect();
+  let Some((last, middle)) = parts.split_last() else {
+    return rest.is_empty();
+  };
+  for part in middle {
+    match rest.find(part) {
+      Some(index) => rest = &rest[index + part.len()..],
+      None => return false,
+    }
+  }
+  rest.ends_with(last)
+}
+
+/// 主机对应的背景色调，取设置中第一个匹配的规则
+pub fn tint(host: &str, cx: &App) -> Option<[u8; 3]> {
+  cx.global::<Settings>()
+    .host_tints
+    .iter()
+    .find(|rule| matches(&rule.host, host))
+    .map(|rule| rule.tint)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn ssh_destination_finds_the_host() {
+    assert_eq!(ssh_destination("ssh prod"), Some("prod".into()));
+    assert_eq!(
+      ssh_destination("ssh deploy@Prod.example.com"),
+      Some("prod.example.com".into())
+    );
+    assert_eq!(
+      ssh_destination("/usr/bin/ssh -p 2222 deploy@prod"),
+      Some("prod".into())
+    );
+    assert_eq!(
+      ssh_destination("ssh -tp2222 prod uptime"),
+      Some("prod".into())
+    );
+    assert_eq!(
+      ssh_destination("ssh -A -i ~/.ssh/id prod"),
+      Some("prod".into())
+    );
+    assert_eq!(
+      ssh_destination("ssh ssh://deploy@prod:2222"),
+      Some("prod".into())
+    );
+    assert_eq!(ssh_destination("ssh [::1]:22"), Some("::1".into()));
+    assert_eq!(ssh_destination("ssh fe80::1"), Some("fe80::1".into()));
+  }
+
+  #[test]
+  fn ssh_destination_ignores_other_commands() {
+    assert_eq!(ssh_destination("ls prod"), None);
+    assert_eq!(ssh_destination("sshfs prod:/ mnt"), None);
+    assert_eq!(ssh_destination("ssh"), None);
+    assert_eq!(ssh_destination("ssh -p 2222"), None);
+    assert_eq!(ssh_destination(""), None);
+  }
+
+  #[test]
+  fn ssh_command_destination_keeps_arguments_with_spaces() {
+    let command: Vec<String> = ["ssh", "-o", "ProxyCommand nc -X connect %h %p", "prod"]
+      .map(String::from)
+      .to_vec();
+    assert_eq!(ssh_command_destination(&command), Some("prod".into()));
+  }
+
+  #[test]
+  fn matches_globs() {
+    assert!(matches("prod", "prod"));
+    assert!(matches("PROD", "prod"));
+    assert!(!matches("prod", "prod2"));
+    assert!(matches("prod*", "prod-db-1"));
+    assert!(matches("*.prod.example.com", "db.prod.example.com"));
+    assert!(!matches("*.prod.example.com", "db.staging.example.com"));
+    assert!(matches("db-*-prod", "db-1-prod"));
+    assert!(!matches("db-*-prod", "db-1-prod-2"));
+    assert!(matches("*", "anything"));
+    assert!(matches("a*b*c", "abc"));
+    assert!(!matches("a*b*c", "acb"));
+  }
+
+  #[test]
+  fn is_disconnect_matches_ssh_messages_at_line_start() {
+    assert!(is_disconnect("Connection to prod closed.\r\n"));
+    assert!(is_disconnect("logout\r\nConnection to prod closed.\r\n"));
+    assert!(is_disconnect(
+      "Connection to 10.0.0.1 closed by remote host.\r\n"
+    ));
+    assert!(is_disconnect("Connection closed by 10.0.0.1 port 22\r\n"));
+    assert!(is_disconnect(
+      "ssh: connect to host prod port 22: Connection refused\r\n"
+    ));
+    assert!(is_disconnect(
+      "ssh: Could not resolve hostname prod: Name or service not known\r\n"
+    ));
+  }
+
+  #[test]
+  fn is_disconnect_ignores_remote_output() {
+    assert!(!is_disconnect(
+      "curl: (7) Failed to connect to api port 443: Connection refused\r\n"
+    ));
+    assert!(!is_disconnect(
+      "nc: connect to db port 5432 (tcp) failed: Connection timed out\r\n"
+    ));
+    assert!(!is_disconnect(
+      "app.log: Connection to db lost, retrying\r\n"
+    ));
+    assert!(!is_disconnect("12:00:01 Connection to prod closed.\r\n"));
+    assert!(!is_disconnect(
+      "Connection to prod closed unexpectedly, retrying\r\n"
+    ));
+    assert!(!is_disconnect("Connection closed by peer\r\n"));
+  }
+}
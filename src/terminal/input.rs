@@ -1,5 +1,11 @@
+use crate::terminal::encoding::TerminalEncoding;
 use crate::terminal::pty::TerminalSize;
+use crate::terminal::search::SearchMatch;
+use alacritty_terminal::grid::Scroll;
+use alacritty_terminal::index::{Direction, Point, Side};
+use alacritty_terminal::selection::Selection;
 use std::fmt;
+use tokio::sync::oneshot;
 
 /// 终端输入事件（UI → Background）
 pub enum TerminalInput {
@@ -12,6 +18,36 @@ pub enum TerminalInput {
   /// 调整终端大小
   Resize(TerminalSize),
 
+  /// 滚动显示区域
+  Scroll(Scroll),
+
+  /// 跳转到上一个/下一个高亮匹配
+  JumpToHighlight(Direction),
+
+  /// 设置或清除选区
+  SetSelection(Option<Selection>),
+
+  /// 扩展选区到指定位置（网格坐标）
+  UpdateSelection(Point, Side),
+
+  /// 取选中的文本（复制、执行选中内容时按需提取，不随每帧生成）
+  SelectionText(oneshot::Sender<Option<String>>),
+
+  /// 在滚动历史中搜索文本
+  Search(String, oneshot::Sender<Vec<SearchMatch>>),
+
+  /// 滚动到指定行（自滚动历史顶部起）并标记为当前匹配
+  RevealRow(usize),
+
+  /// 切换字符编码
+  SetEncoding(TerminalEncoding),
+
+  /// 重置终端状态（RIS），可选保留滚动历史
+  Reset { keep_scrollback: bool },
+
+  /// 重新发送窗口大小，让应用按当前网格重绘
+  Redraw,
+
   /// 获取当前内容（强制刷新）
   Sync,
 
@@ -25,6 +61,27 @@ impl fmt::Debug for TerminalInput {
       TerminalInput::PtyData(data) => f.debug_tuple("PtyData").field(&data.len()).finish(),
       TerminalInput::Write(data) => f.debug_tuple("Write").field(&data.len()).finish(),
       TerminalInput::Resize(size) => f.debug_tuple("Resize").field(size).finish(),
+      TerminalInput::Scroll(scroll) => f.debug_tuple("Scroll").field(scroll).finish(),
+      TerminalInput::JumpToHighlight(direction) => {
+        f.debug_tuple("JumpToHighlight").field(direction).finish()
+      }
+      TerminalInput::SetSelection(selection) => {
+        f.debug_tuple("SetSelection").field(selection).finish()
+      }
+      TerminalInput::UpdateSelection(point, side) => f
+        .debug_tuple("UpdateSelection")
+        .field(point)
+        .field(side)
+        .finish(),
+      TerminalInput::SelectionText(_) => write!(f, "SelectionText"),
+      TerminalInput::Search(query, _) => f.debug_tuple("Search").field(query).finish(),
+      TerminalInput::RevealRow(row) => f.debug_tuple("RevealRow").field(row).finish(),
+      TerminalInput::SetEncoding(encoding) => f.debug_tuple("SetEncoding").field(encoding).finish(),
+      TerminalInput::Reset { keep_scrollback } => f
+        .debug_struct("Reset")
+        .field("keep_scrollback", keep_scrollback)
+        .finish(),
+      TerminalInput::Redraw => write!(f, "Redraw"),
       TerminalInput::Sync => write!(f, "Sync"),
       TerminalInput::Shutdown => write!(f, "Shutdown"),
     }
@@ -0,0 +1,221 @@
+use std::collections::VecDeque;
+
+/// 每个终端保留的输入行数
+pub const INPUT_HISTORY_LEN: usize = 50;
+
+/// 发送到 PTY 的输入行历史，独立于 shell 自己的历史
+///
+/// 从写入 PTY 的字节中还原逐字输入的行，用于串口设备和没有历史功能的 REPL。
+/// 方向键、Tab 补全等会编辑行内容的按键出现后，这一行不再可信，不记录；
+/// 输入时终端处于密码输入状态（按行输入且关闭回显）的行也不记录。
+#[derive(Default)]
+pub struct InputHistory {
+  /// 只还原输入行、不保留历史（私密终端）
+  untracked: bool,
+  /// 最新的在前
+  lines: VecDeque<String>,
+  current: String,
+  /// 当前行被无法还原的按键编辑过
+  edited: bool,
+  /// 当前行有输入是在密码输入状态下发送的
+  masked: bool,
+  /// 上次取走后提交的命令数，见 `take_submitted`
+  submitted: usize,
+}
+
+impl InputHistory {
+  /// 不保留历史的记录器，`record` 仍然返回输入的行
+  pub fn untracked() -> Self {
+    Self {
+      untracked: true,
+      ..Self::default()
+    }
+  }
+
+  /// 历史中的行，最新的在前
+  pub fn lines(&self) -> impl Iterator<Item = &String> {
+    self.lines.iter()
+  }
+
+  /// 记录一次写入；`hidden` 为写入时终端是否处于密码输入状态（见 `LocalModes::hides_input`）。
+  /// 返回这次写入完成的输入行
+  pub fn record(&mut self, data: &[u8], hidden: bool) -> Vec<String> {
+    let mut committed = Vec::new();
+    if hidden {
+      self.masked = true;
+    }
+    let text = String::from_utf8_lossy(data);
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+      match c {
+        '\r' | '\n' => committed.extend(self.commit()),
+        '\x7f' | '\x08' => {
+          self.current.pop();
+        }
+        // Ctrl+C、Ctrl+U 放弃当前行
+        '\x03' | '\x15' => self.clear(),
+        '\x1b' => {
+          // 括号粘贴的标记不影响内容，其他转义序列（方向键等）会编辑行
+          let rest = chars.as_str();
+          if let Some(after) = rest
+            .strip_prefix("[200~")
+            .or_else(|| rest.strip_prefix("[201~"))
+          {
+            chars = after.chars();
+          } else {
+            self.edited = true;
+          }
+        }
+        c if c.is_control() => self.edited = true,
+        c => self.current.push(c),
+      }
+    }
+    committed
+  }
+
+  /// 取走上次调用后提交的命令数（供会话统计使用）
+  ///
+  /// 与历史不同，被方向键、Tab 补全编辑过的行（如调出的历史命令）也计入；空行和密码不计入
+  pub fn take_submitted(&mut self) -> usize {
+    std::mem::take(&mut self.submitted)
+  }
+
+  fn commit(&mut self) -> Option<String> {
+    let line = std::mem::take(&mut self.current);
+    if !self.masked && (self.edited || !line.trim().is_empty()) {
+      self.submitted += 1;
+    }
+    let committed = !self.edited && !self.masked && !line.trim().is_empty();
+    self.clear();
+    if !committed {
+      return None;
+    }
+    if !self.untracked {
+      self.lines.retain(|existing| *existing != line);
+      self.lines.push_front(line.clone());
+      self.lines.truncate(INPUT_HISTORY_LEN);
+    }
+    Some(line)
+  }
+
+  fn clear(&mut self) {
+    self.current.clear();
+    self.edited = false;
+    self.masked = false;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn commits_on_carriage_return_and_newline() {
+    let mut history = InputHistory::default();
+    assert_eq!(history.record(b"ls -la\r", false), vec!["ls -la"]);
+    assert_eq!(history.record(b"pwd\n", false), vec!["pwd"]);
+    assert_eq!(history.record(b"a\rb\r", false), vec!["a", "b"]);
+    assert_eq!(
+      history.lines().collect::<Vec<_>>(),
+      vec!["b", "a", "pwd", "ls -la"]
+    );
+  }
+
+  #[test]
+  fn line_spans_several_writes() {
+    let mut history = InputHistory::default();
+    assert!(history.record(b"git ", false).is_empty());
+    assert!(history.record(b"status", false).is_empty());
+    assert_eq!(history.record(b"\r", false), vec!["git status"]);
+  }
+
+  #[test]
+  fn backspace_removes_characters() {
+    let mut history = InputHistory::default();
+    assert_eq!(history.record(b"lx\x7fs\r", false), vec!["ls"]);
+    assert_eq!(history.record(b"cdd\x08 /\r", false), vec!["cd /"]);
+    // 空行退格不出错
+    assert!(history.record(b"\x7f\x7f\r", false).is_empty());
+  }
+
+  #[test]
+  fn edited_lines_are_dropped() {
+    let mut history = InputHistory::default();
+    // 方向键
+    assert!(history.record(b"ls\x1b[D\x1b[Dx\r", false).is_empty());
+    // Tab 补全
+    assert!(history.record(b"cd Doc\t\r", false).is_empty());
+    // 下一行不受影响
+    assert_eq!(history.record(b"pwd\r", false), vec!["pwd"]);
+  }
+
+  #[test]
+  fn bracketed_paste_markers_do_not_edit() {
+    let mut history = InputHistory::default();
+    assert_eq!(
+      history.record(b"\x1b[200~echo hi\x1b[201~\r", false),
+      vec!["echo hi"]
+    );
+  }
+
+  #[test]
+  fn cancelled_lines_are_cleared() {
+    let mut history = InputHistory::default();
+    assert!(history.record(b"rm -rf\x03", false).is_empty());
+    assert_eq!(history.record(b"ls\r", false), vec!["ls"]);
+    assert!(history.record(b"oops\x15", false).is_empty());
+    assert_eq!(history.record(b"pwd\r", false), vec!["pwd"]);
+  }
+
+  #[test]
+  fn masked_lines_are_dropped() {
+    let mut history = InputHistory::default();
+    assert!(history.record(b"hunter2", true).is_empty());
+    // 密码提示结束后回车也不提交
+    assert!(history.record(b"\r", false).is_empty());
+    assert_eq!(history.record(b"whoami\r", false), vec!["whoami"]);
+    assert_eq!(history.lines().collect::<Vec<_>>(), vec!["whoami"]);
+  }
+
+  #[test]
+  fn blank_and_duplicate_lines() {
+    let mut history = InputHistory::default();
+    assert!(history.record(b"   \r", false).is_empty());
+    history.record(b"ls\rpwd\rls\r", false);
+    assert_eq!(history.lines().collect::<Vec<_>>(), vec!["ls", "pwd"]);
+  }
+
+  #[test]
+  fn untracked_returns_lines_without_keeping_them() {
+    let mut history = InputHistory::untracked();
+    assert_eq!(history.record(b"ssh prod\r", false), vec!["ssh prod"]);
+    assert_eq!(history.lines().count(), 0);
+  }
+
+  #[test]
+  fn keeps_at_most_the_history_length() {
+    let mut history = InputHistory::default();
+    for i in 0..INPUT_HISTORY_LEN + 5 {
+      history.record(format!("echo {}\r", i).as_bytes(), false);
+    }
+    assert_eq!(history.lines().count(), INPUT_HISTORY_LEN);
+  }
+
+  #[test]
+  fn submitted_counts_pasted_and_recalled_commands() {
+    let mut history = InputHistory::default();
+    history.record(b"echo a\recho b\r", false);
+    assert_eq!(history.take_submitted(), 2);
+    history.record(b"\x1b[A\r", false);
+    assert_eq!(history.take_submitted(), 1);
+    assert_eq!(history.take_submitted(), 0);
+  }
+
+  #[test]
+  fn submitted_skips_blank_lines_and_passwords() {
+    let mut history = InputHistory::default();
+    history.record(b"\r  \r", false);
+    history.record(b"hunter2\r", true);
+    assert_eq!(history.take_submitted(), 0);
+  }
+}
@@ -0,0 +1,71 @@
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+use crate::persist;
+
+/// 集成脚本的内容，bash 和 zsh 通用
+const SCRIPT: &str = r#"# catus shell integration, sourced from your shell startup file
+if [ -n "$CATUS_BIN" ]; then
+  # Run a command with its stderr tinted (needs the stderr_tint setting)
+  catus_run() { "$CATUS_BIN" --tag-stderr "$@"; }
+fi
+"#;
+
+/// 集成脚本的位置
+pub fn script_path() -> Option<PathBuf> {
+  persist::config_dir().map(|dir| dir.join("shell").join("catus.sh"))
+}
+
+/// shell 的启动文件，不支持的 shell 返回 None
+pub fn rc_path(shell: &Path) -> Option<PathBuf> {
+  let home = dirs::home_dir()?;
+  match shell.file_name()?.to_str()? {
+    "bash" => Some(home.join(".bashrc")),
+    "zsh" => Some(
+      std::env::var_os("ZDOTDIR")
+        .map(PathBuf::from)
+        .unwrap_or(home)
+        .join(".zshrc"),
+    ),
+    _ => None,
+  }
+}
+
+/// 启动文件是否已经加载集成脚本
+pub fn is_installed(shell: &Path) -> bool {
+  let (Some(rc), Some(script)) = (rc_path(shell), script_path()) else {
+    return false;
+  };
+  std::fs::read_to_string(rc)
+    .map(|contents| contents.contains(&*script.to_string_lossy()))
+    .unwrap_or(false)
+}
+
+/// 写入集成脚本，并在 shell 启动文件末尾加载它；返回修改的启动文件
+pub fn install(shell: &Path) -> Result<PathBuf, String> {
+  let rc = rc_path(shell)
+    .ok_or_else(|| format!("Shell integration does not support {}", shell.display()))?;
+  let script = script_path().ok_or("No config directory")?;
+  persist::write_atomic(&script, SCRIPT.as_bytes())
+    .map_err(|e| format!("Failed to write {}: {}", script.display(), e))?;
+  if is_installed(shell) {
+    return Ok(rc);
+  }
+
+  // 追加而不是重写，启动文件可能是指向 dotfiles 仓库的符号链接
+  let needs_newline = std::fs::read(&rc)
+    .map(|contents| !contents.is_empty() && !contents.ends_with(b"\n"))
+    .unwrap_or(false);
+  let line = format!(
+    "{}\n# Added by catus\n[ -f '{1}' ] && . '{1}'\n",
+    if needs_newline { "\n" } else { "" },
+    script.display()
+  );
+  std::fs::OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(&rc)
+    .and_then(|mut file| file.write_all(line.as_bytes()))
+    .map_err(|e| format!("Failed to update {}: {}", rc.display(), e))?;
+  Ok(rc)
+}
@@ -0,0 +1,210 @@
+use serde::{Deserialize, Serialize};
+
+/// Backspace 键发送的字符
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackspaceKey {
+  /// DEL（`^?`，0x7f），大多数系统的默认值
+  #[default]
+  Del,
+  /// BS（`^H`，0x08），部分老系统和串口设备需要
+  CtrlH,
+}
+
+impl BackspaceKey {
+  pub fn bytes(self) -> &'static [u8] {
+    match self {
+      BackspaceKey::Del => b"\x7f",
+      BackspaceKey::CtrlH => b"\x08",
+    }
+  }
+}
+
+/// Delete 键发送的字符
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeleteKey {
+  /// VT220 的 `ESC [ 3 ~`
+  #[default]
+  Escape,
+  /// DEL（`^?`，0x7f），用于 Backspace 发送 `^H` 的系统
+  Del,
+}
+
+impl DeleteKey {
+  pub fn bytes(self) -> &'static [u8] {
+    match self {
+      DeleteKey::Escape => b"\x1b[3~",
+      DeleteKey::Del => b"\x7f",
+    }
+  }
+}
+
+/// 应用设置的 Backspace 模式，即 DECBKM（`CSI ? 67 h` 发送 `^H`，`CSI ? 67 l` 发送 `^?`）
+///
+/// alacritty 不处理这个模式，这里扫描 PTY 输出自行记录；应用没有设置过时为 None，使用设置中的值。
+/// 应用发送 RIS（`ESC c`，`reset` 和 `tput reset` 使用）或 DECSTR（`CSI ! p`）后回到设置中的值。
+#[derive(Default)]
+pub struct BackarrowModeScanner {
+  state: State,
+  params: Vec<u32>,
+  mode: Option<BackspaceKey>,
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+enum State {
+  #[default]
+  Ground,
+  Escape,
+  /// `CSI ?` 之后的参数
+  PrivateCsi,
+  /// 其他 CSI，跳过到终止字节
+  Csi,
+  /// `CSI !`，后面是 `p` 时为 DECSTR
+  SoftReset,
+}
+
+/// DECBKM 的模式编号
+const DECBKM: u32 = 67;
+/// CSI 参数的最大个数，超出后忽略多余的参数
+const MAX_PARAMS: usize = 16;
+
+impl BackarrowModeScanner {
+  pub fn mode(&self) -> Option<BackspaceKey> {
+    self.mode
+  }
+
+  /// 终端重置后回到设置中的值
+  pub fn reset(&mut self) {
+    *self = Self::default();
+  }
+
+  /// 扫描输出数据
+  pub fn scan(&mut self, data: &[u8]) {
+    for &byte in data {
+      match (self.state, byte) {
+        (_, 0x1b) => self.state = State::Escape,
+        (State::Escape, b'[') => self.state = State::Csi,
+        (State::Escape, b'c') => {
+          self.mode = None;
+          self.state = State::Ground;
+        }
+        (State::Escape, _) => self.state = State::Ground,
+        (State::Csi, b'?') => {
+          self.params.clear();
+          self.params.push(0);
+          self.state = State::PrivateCsi;
+        }
+        (State::PrivateCsi, b'0'..=b'9') => {
+          if let Some(param) = self.params.last_mut() {
+            *param = param
+              .saturating_mul(10)
+              .saturating_add((byte - b'0') as u32);
+          }
+        }
+        (State::PrivateCsi, b';') => {
+          if self.params.len() < MAX_PARAMS {
+            self.params.push(0);
+          }
+        }
+        (State::PrivateCsi, b'h' | b'l') => {
+          if self.params.contains(&DECBKM) {
+            self.mode = Some(if byte == b'h' {
+              BackspaceKey::CtrlH
+            } else {
+              BackspaceKey::Del
+            });
+          }
+          self.state = State::Ground;
+        }
+        (State::Csi, b'!') => self.state = State::SoftReset,
+        (State::SoftReset, b'p') => {
+          self.mode = None;
+          self.state = State::Ground;
+        }
+        (State::Csi | State::PrivateCsi | State::SoftReset, 0x40..=0x7e) => {
+          self.state = State::Ground
+        }
+        (State::Csi | State::PrivateCsi | State::SoftReset, _) => {}
+        (State::Ground, _) => {}
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn scan(chunks: &[&[u8]]) -> Option<BackspaceKey> {
+    let mut scanner = BackarrowModeScanner::default();
+    for chunk in chunks {
+      scanner.scan(chunk);
+    }
+    scanner.mode()
+  }
+
+  #[test]
+  fn set_and_reset_decbkm() {
+    assert_eq!(scan(&[b"\x1b[?67h"]), Some(BackspaceKey::CtrlH));
+    assert_eq!(scan(&[b"\x1b[?67l"]), Some(BackspaceKey::Del));
+    assert_eq!(scan(&[b"\x1b[?67h\x1b[?67l"]), Some(BackspaceKey::Del));
+    assert_eq!(scan(&[b"plain output"]), None);
+  }
+
+  #[test]
+  fn other_modes_are_ignored() {
+    assert_eq!(scan(&[b"\x1b[?25h\x1b[?1049l"]), None);
+    assert_eq!(scan(&[b"\x1b[?670h"]), None);
+    // 非私有模式（ANSI 模式 67）不是 DECBKM
+    assert_eq!(scan(&[b"\x1b[67h"]), None);
+  }
+
+  #[test]
+  fn multiple_params() {
+    assert_eq!(scan(&[b"\x1b[?1;67h"]), Some(BackspaceKey::CtrlH));
+    assert_eq!(scan(&[b"\x1b[?67;1049l"]), Some(BackspaceKey::Del));
+  }
+
+  #[test]
+  fn split_across_chunks() {
+    let whole: &[u8] = b"ab\x1b[?1;67hcd";
+    for split in 1..whole.len() {
+      let (first, second) = whole.split_at(split);
+      assert_eq!(
+        scan(&[first, second]),
+        Some(BackspaceKey::CtrlH),
+        "split at {}",
+        split
+      );
+    }
+  }
+
+  #[test]
+  fn ris_and_decstr_reset() {
+    assert_eq!(scan(&[b"\x1b[?67h\x1bc"]), None);
+    assert_eq!(scan(&[b"\x1b[?67h\x1b[!p"]), None);
+    assert_eq!(scan(&[b"\x1b[?67h\x1b", b"c"]), None);
+    assert_eq!(scan(&[b"\x1b[?67h\x1b[!", b"p"]), None);
+    // 重置后可以再次设置
+    assert_eq!(scan(&[b"\x1bc\x1b[?67h"]), Some(BackspaceKey::CtrlH));
+  }
+
+  #[test]
+  fn reset_clears_mode_and_state() {
+    let mut scanner = BackarrowModeScanner::default();
+    scanner.scan(b"\x1b[?67h\x1b[?6");
+    scanner.reset();
+    assert_eq!(scanner.mode(), None);
+    scanner.scan(b"7h");
+    assert_eq!(scanner.mode(), None);
+  }
+
+  #[test]
+  fn erase_key_bytes() {
+    assert_eq!(BackspaceKey::Del.bytes(), b"\x7f");
+    assert_eq!(BackspaceKey::CtrlH.bytes(), b"\x08");
+    assert_eq!(DeleteKey::Escape.bytes(), b"\x1b[3~");
+    assert_eq!(DeleteKey::Del.bytes(), b"\x7f");
+  }
+}
@@ -1,8 +1,11 @@
 use crate::terminal::Pty;
+#[cfg(unix)]
+use crate::terminal::pty::LocalModes;
 use crate::terminal::pty::TerminalSize;
 use anyhow::{Context, Result};
 use async_channel::{Receiver, Sender, unbounded};
 use portable_pty::{Child, CommandBuilder, PtySize};
+use std::ffi::OsString;
 use std::io::{Read, Write};
 use std::sync::{Arc, Mutex};
 use std::thread;
@@ -34,7 +37,12 @@ impl LocalPty {
   /// # Arguments
   /// * `size` - 终端尺寸
   /// * `command` - 可选的命令，如果为 None 则启动系统默认 shell
-  pub fn new(size: TerminalSize, command: Option<&str>) -> Result<Self> {
+  /// * `env` - 附加的环境变量
+  pub fn new(
+    size: TerminalSize,
+    command: Option<&str>,
+    env: &[(String, OsString)],
+  ) -> Result<Self> {
     let pty_system = portable_pty::native_pty_system();
 
     let pty_size = PtySize {
@@ -49,7 +57,7 @@ impl LocalPty {
       .with_context(|| "Failed to open PTY")?;
 
     // 获取要执行的命令，如果没有提供则使用系统默认 shell
-    let cmd = if let Some(cmd) = command {
+    let mut cmd = if let Some(cmd) = command {
       CommandBuilder::new(cmd)
     } else {
       // 使用系统默认 shell
@@ -66,6 +74,13 @@ impl LocalPty {
       }
     };
 
+    // 桌面环境启动时继承的 TERM 可能未设置或为 dumb
+    cmd.env("TERM", "xterm-256color");
+    cmd.env("COLORTERM", "truecolor");
+    for (key, value) in env {
+      cmd.env(key, value);
+    }
+
     let child = pty_pair
       .slave
       .spawn_command(cmd)
@@ -209,6 +224,35 @@ impl Pty for LocalPty {
   fn process_id(&self) -> Option<u32> {
     self.process_id
   }
+
+  #[cfg(unix)]
+  fn foreground_process(&self) -> Option<u32> {
+    let leader = self.master.lock().ok()?.process_group_leader()?;
+    u32::try_from(leader).ok().filter(|&pid| pid > 0)
+  }
+
+  /// 只支持 Linux（读取 `/proc`）
+  #[cfg(target_os = "linux")]
+  fn working_directory(&self) -> Option<std::path::PathBuf> {
+    std::fs::read_link(format!("/proc/{}/cwd", self.process_id?)).ok()
+  }
+
+  #[cfg(unix)]
+  fn local_modes(&self) -> Option<LocalModes> {
+    let fd = self.master.lock().ok()?.as_raw_fd()?;
+    let mut termios = std::mem::MaybeUninit::<libc::termios>::uninit();
+    // SAFETY: fd 在 master 存活期间有效，tcgetattr 成功时会完整写入 termios
+    let termios = unsafe {
+      if libc::tcgetattr(fd, termios.as_mut_ptr()) != 0 {
+        return None;
+      }
+      termios.assume_init()
+    };
+    Some(LocalModes {
+      echo: termios.c_lflag & libc::ECHO != 0,
+      canonical: termios.c_lflag & libc::ICANON != 0,
+    })
+  }
 }
 
 impl Drop for LocalPty {
@@ -0,0 +1,118 @@
+use std::borrow::Cow;
+
+/// 预览中每行最多显示的字符数
+pub const PREVIEW_LINE_CAP: usize = 500;
+/// 预览中最多显示的行数
+pub const PREVIEW_MAX_LINES: usize = 40;
+
+/// 软截断文本用于预览
+///
+/// 压缩过的 JSON 等超长单行会让对话框排版卡住，超过 `line_cap` 个字符的行截断并加省略号，
+/// 超过 `max_lines` 的行省略。返回预览文本和被隐藏的字符数（为 0 时原样返回）。
+pub fn soft_cap(text: &str, line_cap: usize, max_lines: usize) -> (Cow<'_, str>, usize) {
+  let mut preview = String::new();
+  let mut hidden = 0;
+  for (index, line) in text.split('\n').enumerate() {
+    if index >= max_lines {
+      hidden += line.chars().count() + 1;
+      continue;
+    }
+    if index > 0 {
+      preview.push('\n');
+    }
+    match line.char_indices().nth(line_cap) {
+      Some((end, _)) => {
+        let rest = line[end..].chars().count();
+        preview.push_str(&line[..end]);
+        preview.push_str(&format!("… (+{} chars)", rest));
+        hidden += rest;
+      }
+      None => preview.push_str(line),
+    }
+  }
+
+  if hidden == 0 {
+    return (Cow::Borrowed(text), 0);
+  }
+  let omitted_lines = text.split('\n').count().saturating_sub(max_lines);
+  if omitted_lines > 0 {
+    preview.push_str(&format!("\n… ({} more lines)", omitted_lines));
+  }
+  (Cow::Owned(preview), hidden)
+}
+
+/// 按字符数把每行切成不超过 `width` 个字符的显示行，用于只渲染可见行的完整预览
+pub fn wrap_rows(text: &str, width: usize) -> Vec<String> {
+  let width = width.max(1);
+  let mut rows = Vec::new();
+  for line in text.split('\n') {
+    let mut rest = line;
+    loop {
+      match rest.char_indices().nth(width) {
+        Some((end, _)) => {
+          rows.push(rest[..end].to_string());
+          rest = &rest[end..];
+        }
+        None => {
+          rows.push(rest.to_string());
+          break;
+        }
+      }
+    }
+  }
+  rows
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn line_at_cap_is_kept() {
+    let text = "a".repeat(10);
+    let (preview, hidden) = soft_cap(&text, 10, 5);
+    assert!(matches!(preview, Cow::Borrowed(_)));
+    assert_eq!(preview, text);
+    assert_eq!(hidden, 0);
+  }
+
+  #[test]
+  fn line_over_cap_is_cut() {
+    let text = "a".repeat(11);
+    let (preview, hidden) = soft_cap(&text, 10, 5);
+    assert_eq!(preview, format!("{}… (+1 chars)", "a".repeat(10)));
+    assert_eq!(hidden, 1);
+  }
+
+  #[test]
+  fn cut_keeps_multibyte_chars_whole() {
+    let text = "ab中文字";
+    let (preview, hidden) = soft_cap(text, 3, 5);
+    assert_eq!(preview, "ab中… (+2 chars)");
+    assert_eq!(hidden, 2);
+  }
+
+  #[test]
+  fn lines_over_max_are_omitted() {
+    let text = "one\ntwo\nthree\nfour";
+    let (preview, hidden) = soft_cap(text, 10, 2);
+    assert_eq!(preview, "one\ntwo\n… (2 more lines)");
+    assert_eq!(hidden, "three\n".len() + "four\n".len());
+  }
+
+  #[test]
+  fn max_lines_exactly_is_kept() {
+    let text = "one\ntwo";
+    let (preview, hidden) = soft_cap(text, 10, 2);
+    assert_eq!(preview, text);
+    assert_eq!(hidden, 0);
+  }
+
+  #[test]
+  fn wrap_rows_splits_long_lines() {
+    assert_eq!(wrap_rows("abcde\nf", 2), ["ab", "cd", "e", "f"]);
+    assert_eq!(wrap_rows("中文字", 2), ["中文", "字"]);
+    assert_eq!(wrap_rows("ab", 2), ["ab"]);
+    assert_eq!(wrap_rows("", 2), [""]);
+  }
+}
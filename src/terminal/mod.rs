@@ -1,9 +1,25 @@
 pub mod content;
+pub mod encoding;
+pub mod environment;
+pub mod flood;
+pub mod highlight;
+pub mod host;
 pub mod input;
+pub mod input_history;
+pub mod integration;
+pub mod keys;
 pub mod local_pty;
+pub mod long_line;
+pub mod overflow;
+pub mod pool;
+pub mod print;
 pub mod pty;
+pub mod search;
+pub mod stderr;
 pub mod terminal;
 pub mod terminal_element;
+pub mod unseen;
+pub mod untrusted;
 pub mod view;
 
 // 重导出主要类型
@@ -13,6 +29,67 @@ pub use content::{
 };
 pub use local_pty::LocalPty;
 pub use pty::{Pty, TerminalSize};
-pub use terminal::Terminal;
+pub use terminal::{Terminal, TerminalOptions};
 pub use terminal_element::TerminalElement;
 pub use view::TerminalView;
+
+use gpui::{App, KeyBinding};
+
+use crate::tab_kind::TabKinds;
+
+/// 注册终端 Tab 类型和快捷键
+pub fn init(cx: &mut App) {
+  TabKinds::register(&view::TERMINAL_TAB_KIND, cx);
+  TabKinds::register(&view::ENVIRONMENT_TERMINAL_TAB_KIND, cx);
+  TabKinds::register(&view::PRIVATE_TERMINAL_TAB_KIND, cx);
+  TabKinds::register(&view::UNTRUSTED_TERMINAL_TAB_KIND, cx);
+  pool::init(cx);
+  print::init(cx);
+
+  use view::{
+    Copy, KEY_CONTEXT, Paste, Print, ResetZoom, ResizeWithKeyboard, RunSelection,
+    RunSelectionInNewTab, ScrollToNextHighlight, ScrollToPreviousHighlight, ToggleComposer,
+    ToggleInputHistory, ZoomIn, ZoomOut,
+  };
+
+  #[cfg(target_os = "macos")]
+  cx.bind_keys([
+    KeyBinding::new("cmd-shift-down", ScrollToNextHighlight, Some(KEY_CONTEXT)),
+    KeyBinding::new("cmd-shift-up", ScrollToPreviousHighlight, Some(KEY_CONTEXT)),
+    KeyBinding::new("cmd-c", Copy, Some(KEY_CONTEXT)),
+    KeyBinding::new("cmd-v", Paste, Some(KEY_CONTEXT)),
+    KeyBinding::new("cmd-enter", RunSelection, Some(KEY_CONTEXT)),
+    KeyBinding::new("cmd-shift-enter", RunSelectionInNewTab, Some(KEY_CONTEXT)),
+    KeyBinding::new("cmd-=", ZoomIn, Some(KEY_CONTEXT)),
+    KeyBinding::new("cmd--", ZoomOut, Some(KEY_CONTEXT)),
+    KeyBinding::new("cmd-0", ResetZoom, Some(KEY_CONTEXT)),
+    KeyBinding::new("cmd-shift-h", ToggleInputHistory, Some(KEY_CONTEXT)),
+    KeyBinding::new("cmd-shift-e", ToggleComposer, Some(KEY_CONTEXT)),
+    KeyBinding::new("cmd-p", Print, Some(KEY_CONTEXT)),
+    KeyBinding::new("cmd-shift-r", ResizeWithKeyboard, Some(KEY_CONTEXT)),
+  ]);
+  #[cfg(not(target_os = "macos"))]
+  cx.bind_keys([
+    KeyBinding::new("ctrl-shift-down", ScrollToNextHighlight, Some(KEY_CONTEXT)),
+    KeyBinding::new(
+      "ctrl-shift-up",
+      ScrollToPreviousHighlight,
+      Some(KEY_CONTEXT),
+    ),
+    KeyBinding::new("ctrl-shift-c", Copy, Some(KEY_CONTEXT)),
+    KeyBinding::new("ctrl-shift-v", Paste, Some(KEY_CONTEXT)),
+    KeyBinding::new("ctrl-shift-enter", RunSelection, Some(KEY_CONTEXT)),
+    KeyBinding::new(
+      "ctrl-alt-shift-enter",
+      RunSelectionInNewTab,
+      Some(KEY_CONTEXT),
+    ),
+    KeyBinding::new("ctrl-=", ZoomIn, Some(KEY_CONTEXT)),
+    KeyBinding::new("ctrl--", ZoomOut, Some(KEY_CONTEXT)),
+    KeyBinding::new("ctrl-0", ResetZoom, Some(KEY_CONTEXT)),
+    KeyBinding::new("ctrl-shift-h", ToggleInputHistory, Some(KEY_CONTEXT)),
+    KeyBinding::new("ctrl-shift-e", ToggleComposer, Some(KEY_CONTEXT)),
+    KeyBinding::new("ctrl-shift-p", Print, Some(KEY_CONTEXT)),
+    KeyBinding::new("ctrl-shift-r", ResizeWithKeyboard, Some(KEY_CONTEXT)),
+  ]);
+}
@@ -0,0 +1,79 @@
+/// 检测应用是否在当前网格之外绘制
+///
+/// 调整大小后，全屏应用有时仍按旧尺寸输出，光标定位（CUP）会超出网格而被截断，
+/// 导致画面错乱。这里扫描 PTY 输出中的 `CSI row ; col H/f`，记录超出网格的最大坐标。
+#[derive(Default)]
+pub struct GridOverflowDetector {
+  state: State,
+  params: Vec<u32>,
+  /// 超出网格的最大行、列（从 1 开始）
+  overflow: Option<(usize, usize)>,
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+enum State {
+  #[default]
+  Ground,
+  Escape,
+  Csi,
+}
+
+/// CSI 参数的最大个数，超出后忽略该序列
+const MAX_PARAMS: usize = 8;
+
+impl GridOverflowDetector {
+  /// 超出网格的最大行、列
+  pub fn overflow(&self) -> Option<(usize, usize)> {
+    self.overflow
+  }
+
+  /// 清除记录（调整大小、重绘或退出备用屏幕后调用）
+  pub fn reset(&mut self) {
+    self.overflow = None;
+  }
+
+  /// 扫描输出数据
+  pub fn scan(&mut self, data: &[u8], rows: usize, cols: usize) {
+    for &byte in data {
+      match (self.state, byte) {
+        (_, 0x1b) => self.state = State::Escape,
+        (State::Escape, b'[') => {
+          self.params.clear();
+          self.params.push(0);
+          self.state = State::Csi;
+        }
+        (State::Escape, _) => self.state = State::Ground,
+        (State::Csi, b'0'..=b'9') => {
+          if let Some(param) = self.params.last_mut() {
+            *param = param
+              .saturating_mul(10)
+              .saturating_add((byte - b'0') as u32);
+          }
+        }
+        (State::Csi, b';') => {
+          if self.params.len() < MAX_PARAMS {
+            self.params.push(0);
+          }
+        }
+        (State::Csi, b'H' | b'f') => {
+          self.check(rows, cols);
+          self.state = State::Ground;
+        }
+        // 其他终止字节
+        (State::Csi, 0x40..=0x7e) => self.state = State::Ground,
+        (State::Csi, _) => {}
+        (State::Ground, _) => {}
+      }
+    }
+  }
+
+  fn check(&mut self, rows: usize, cols: usize) {
+    let row = self.params.first().copied().unwrap_or(0).max(1) as usize;
+    let col = self.params.get(1).copied().unwrap_or(0).max(1) as usize;
+    if row <= rows && col <= cols {
+      return;
+    }
+    let (max_row, max_col) = self.overflow.unwrap_or((rows, cols));
+    self.overflow = Some((max_row.max(row), max_col.max(col)));
+  }
+}
@@ -0,0 +1,78 @@
+use std::ffi::OsString;
+
+use gpui::{App, AppContext as _, Global};
+
+use crate::settings::Settings;
+use crate::terminal::{LocalPty, TerminalSize, view};
+
+/// 启动 shell 用到的参数，设置变化后预先启动的 shell 不再可用
+#[derive(Clone, PartialEq)]
+struct SpawnKey {
+  shell: Option<String>,
+  env: Vec<(String, OsString)>,
+}
+
+/// 预先启动的空闲 shell，新建终端时直接取用，省去同步启动 shell 的等待
+///
+/// 只保留一个；取走后在后台补充。注入了环境变量集的终端需要不同的环境变量，
+/// 仍然同步启动（在已启动的 shell 中输入 export 会回显并进入 shell 历史）。
+#[derive(Default)]
+pub struct TerminalPool {
+  idle: Option<(SpawnKey, LocalPty)>,
+  spawning: bool,
+}
+
+impl Global for TerminalPool {}
+
+impl SpawnKey {
+  /// 按当前设置启动普通终端时的参数
+  fn current(cx: &App) -> Self {
+    Self {
+      shell: cx.global::<Settings>().shell.clone(),
+      env: view::shell_env(cx),
+    }
+  }
+}
+
+/// 取出按当前设置启动的空闲 PTY，并在后台补充下一个；没有时返回 None
+pub fn take(cx: &mut App) -> Option<LocalPty> {
+  let key = SpawnKey::current(cx);
+  let idle = cx.default_global::<TerminalPool>().idle.take();
+  let pty = idle.and_then(|(idle_key, pty)| (idle_key == key).then_some(pty));
+  replenish(key, cx);
+  pty
+}
+
+/// 在后台启动一个空闲 shell；关闭了预启动或已有空闲 shell 时不做任何事
+fn replenish(key: SpawnKey, cx: &mut App) {
+  if !cx.global::<Settings>().prewarm_terminal {
+    return;
+  }
+  let pool = cx.default_global::<TerminalPool>();
+  if pool.idle.is_some() || pool.spawning {
+    return;
+  }
+  pool.spawning = true;
+  let task = cx.background_spawn({
+    let key = key.clone();
+    async move { LocalPty::new(TerminalSize::default_size(), key.shell.as_deref(), &key.env) }
+  });
+  cx.spawn(async move |cx| {
+    let pty = task.await;
+    let _ = cx.update(|cx| {
+      let pool = cx.global_mut::<TerminalPool>();
+      pool.spawning = false;
+      match pty {
+        Ok(pty) => pool.idle = Some((key, pty)),
+        Err(e) => eprintln!("Failed to prewarm terminal: {}", e),
+      }
+    });
+  })
+  .detach();
+}
+
+/// 启动时预先启动一个 shell
+pub fn init(cx: &mut App) {
+  let key = SpawnKey::current(cx);
+  replenish(key, cx);
+}
@@ -0,0 +1,253 @@
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use alacritty_terminal::term::cell::Flags;
+use alacritty_terminal::vte::ansi::NamedColor;
+use gpui::App;
+
+use crate::terminal::content::TerminalContent;
+
+/// 打印的内容和格式
+#[derive(Clone, Copy, Debug)]
+pub struct PrintOptions {
+  /// 只打印选区（仅视口中可见的部分），否则打印整个屏幕
+  pub selection_only: bool,
+  /// 保留终端的前景色、背景色，否则按白底黑字打印
+  pub colors: bool,
+}
+
+/// 一段样式相同的连续文本
+#[derive(PartialEq, Eq)]
+struct Style {
+  fg: [u8; 3],
+  bg: [u8; 3],
+  bold: bool,
+  italic: bool,
+  underline: bool,
+}
+
+/// 将终端屏幕转换为 HTML 文档，打开后会弹出打印对话框
+pub fn to_html(content: &TerminalContent, options: PrintOptions) -> String {
+  let default_fg = content.palette_rgb(NamedColor::Foreground as usize);
+  let default_bg = content.background_rgb();
+  let (page_fg, page_bg) = if options.colors {
+    (default_fg, default_bg)
+  } else {
+    ([0, 0, 0], [0xff, 0xff, 0xff])
+  };
+  let num_columns = content.terminal_bounds.num_columns();
+
+  let mut rows: Vec<String> = Vec::new();
+  let mut line = None;
+  let mut row = String::new();
+  let mut run = String::new();
+  let mut style: Option<Style> = None;
+  for indexed in &content.cells {
+    let row_index = indexed.point.line.0;
+    if line != Some(row_index) {
+      if line.is_some() {
+        flush_run(&mut row, &mut run, style.take(), page_fg, page_bg);
+        rows.push(std::mem::take(&mut row));
+      }
+      line = Some(row_index);
+    }
+
+    let cell = &indexed.cell;
+    if cell.flags.contains(Flags::WIDE_CHAR_SPACER) {
+      continue;
+    }
+    if options.selection_only {
+      let selected = content
+        .selection
+        .as_ref()
+        .and_then(|selection| selection.columns_in_row(row_index, num_columns))
+        .is_some_and(|(first, last)| (first..=last).contains(&indexed.point.column.0));
+      if !selected {
+        continue;
+      }
+    }
+
+    let cell_style = if options.colors {
+      let mut fg = content.resolve_color(&cell.fg);
+      let mut bg = content.resolve_color(&cell.bg);
+      if cell.flags.contains(Flags::INVERSE) {
+        std::mem::swap(&mut fg, &mut bg);
+      }
+      Style {
+        fg,
+        bg,
+        bold: cell.flags.intersects(Flags::BOLD),
+        italic: cell.flags.contains(Flags::ITALIC),
+        underline: cell.flags.intersects(Flags::ALL_UNDERLINES),
+      }
+    } else {
+      Style {
+        fg: page_fg,
+        bg: page_bg,
+        bold: cell.flags.intersects(Flags::BOLD),
+        italic: cell.flags.contains(Flags::ITALIC),
+        underline: false,
+      }
+    };
+    if style.as_ref() != Some(&cell_style) {
+      flush_run(&mut row, &mut run, style.take(), page_fg, page_bg);
+      style = Some(cell_style);
+    }
+    run.push(cell.c);
+    run.extend(cell.zerowidth().into_iter().flatten());
+  }
+  if line.is_some() {
+    flush_run(&mut row, &mut run, style, page_fg, page_bg);
+    rows.push(row);
+  }
+
+  // 去掉首尾的空行；选区模式下未选中的行也是空行
+  while rows.last().is_some_and(|row| row.trim_end().is_empty()) {
+    rows.pop();
+  }
+  let first = rows
+    .iter()
+    .position(|row| !row.trim_end().is_empty())
+    .unwrap_or(rows.len());
+
+  let mut html = String::new();
+  let _ = write!(
+    html,
+    "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n\
+     @page {{ margin: 1.5cm; }}\n\
+     body {{ margin: 0; color: {}; background: {}; \
+     -webkit-print-color-adjust: exact; print-color-adjust: exact; }}\n\
+     pre {{ margin: 0; font: 9pt Menlo, Consolas, \"DejaVu Sans Mono\", monospace; \
+     white-space: pre-wrap; overflow-wrap: anywhere; }}\n\
+     </style>\n</head>\n<body onload=\"window.print()\">\n<pre>",
+    escape(if content.title.is_empty() {
+      "Terminal"
+    } else {
+      &content.title
+    }),
+    css_color(page_fg),
+    css_color(page_bg),
+  );
+  for row in &rows[first..] {
+    html.push_str(row.trim_end_matches(' '));
+    html.push('\n');
+  }
+  html.push_str("</pre>\n</body>\n</html>\n");
+  html
+}
+
+/// 将一段文本写入当前行，样式与页面默认样式相同时不加 span
+fn flush_run(
+  row: &mut String,
+  run: &mut String,
+  style: Option<Style>,
+  page_fg: [u8; 3],
+  page_bg: [u8; 3],
+) {
+  let text = escape(run);
+  run.clear();
+  let Some(style) = style else {
+    row.push_str(&text);
+    return;
+  };
+  let mut css = String::new();
+  if style.fg != page_fg {
+    let _ = write!(css, "color:{};", css_color(style.fg));
+  }
+  if style.bg != page_bg {
+    let _ = write!(css, "background:{};", css_color(style.bg));
+  }
+  if style.bold {
+    css.push_str("font-weight:bold;");
+  }
+  if style.italic {
+    css.push_str("font-style:italic;");
+  }
+  if style.underline {
+    css.push_str("text-decoration:underline;");
+  }
+  if css.is_empty() {
+    row.push_str(&text);
+  } else {
+    let _ = write!(row, "<span style=\"{}\">{}</span>", css, text);
+  }
+}
+
+fn css_color([r, g, b]: [u8; 3]) -> String {
+  format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+fn escape(text: &str) -> String {
+  let mut escaped = String::with_capacity(text.len());
+  for c in text.chars() {
+    match c {
+      '&' => escaped.push_str("&amp;"),
+      '<' => escaped.push_str("&lt;"),
+      '>' => escaped.push_str("&gt;"),
+      '"' => escaped.push_str("&quot;"),
+      c => escaped.push(c),
+    }
+  }
+  escaped
+}
+
+/// 存放打印文件的目录，只有当前用户可访问，第一次打印时创建，应用退出时删除
+static PRINT_DIR: OnceLock<Result<PathBuf, String>> = OnceLock::new();
+
+fn create_print_dir() -> Result<&'static PathBuf, String> {
+  PRINT_DIR
+    .get_or_init(|| {
+      let base = dirs::runtime_dir().unwrap_or_else(std::env::temp_dir);
+      let dir = base.join(format!("catus-print-{}", std::process::id()));
+      let create = || -> std::io::Result<()> {
+        // 同一进程 ID 的旧目录来自崩溃的进程，不能沿用其中的文件
+        if dir.exists() {
+          std::fs::remove_dir_all(&dir)?;
+        }
+        let mut builder = std::fs::DirBuilder::new();
+        #[cfg(unix)]
+        std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+        builder.create(&dir)
+      };
+      create()
+        .map(|_| dir.clone())
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))
+    })
+    .as_ref()
+    .map_err(Clone::clone)
+}
+
+/// 将 HTML 写入临时文件，交给系统默认的浏览器打开并打印（可在打印对话框中另存为 PDF）
+///
+/// 文件只有当前用户可读写，浏览器可能稍后才读取，因此留到应用退出时删除。
+pub fn write_temp_file(html: &str) -> Result<PathBuf, String> {
+  static NEXT: AtomicUsize = AtomicUsize::new(0);
+  let dir = create_print_dir()?;
+  let path = dir.join(format!(
+    "print-{}.html",
+    NEXT.fetch_add(1, Ordering::Relaxed)
+  ));
+  let mut options = std::fs::OpenOptions::new();
+  options.write(true).create_new(true);
+  #[cfg(unix)]
+  std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+  options
+    .open(&path)
+    .and_then(|mut file| file.write_all(html.as_bytes()))
+    .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+  Ok(path)
+}
+
+/// 应用退出时删除打印文件
+pub fn init(cx: &mut App) {
+  cx.on_app_quit(|_| {
+    if let Some(Ok(dir)) = PRINT_DIR.get() {
+      let _ = std::fs::remove_dir_all(dir);
+    }
+    async {}
+  })
+  .detach();
+}
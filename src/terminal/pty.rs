@@ -1,5 +1,6 @@
 use anyhow::Result;
 use async_channel::Receiver;
+use std::path::PathBuf;
 
 /// 终端尺寸
 #[derive(Clone, Copy, Debug)]
@@ -32,6 +33,24 @@ impl TerminalSize {
   }
 }
 
+/// 终端的本地模式（termios 的 `c_lflag`）中与输入相关的标志
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LocalModes {
+  /// ECHO：由内核回显输入
+  pub echo: bool,
+  /// ICANON：内核按行缓冲输入
+  pub canonical: bool,
+}
+
+impl LocalModes {
+  /// 是否在输入密码（sudo、ssh、`read -s`）：按行输入但关闭了回显
+  ///
+  /// readline、fish 和大多数 REPL 以非规范模式运行并自行回显，同样关闭了 ECHO，不算密码输入
+  pub fn hides_input(self) -> bool {
+    self.canonical && !self.echo
+  }
+}
+
 /// PTY 抽象
 ///
 /// 参考 Zed 的设计，所有方法使用 `&self` 而非 `&mut self`，
@@ -79,4 +98,19 @@ pub trait Pty: Send + Sync {
   /// # Returns
   /// 如果可用，返回进程 ID，否则返回 None
   fn process_id(&self) -> Option<u32>;
+
+  /// 终端当前的本地模式，无法判断时返回 None
+  fn local_modes(&self) -> Option<LocalModes> {
+    None
+  }
+
+  /// 终端前台进程组的 ID（即其首进程的 PID），无法判断时返回 None
+  fn foreground_process(&self) -> Option<u32> {
+    None
+  }
+
+  /// shell 的当前工作目录，无法判断时返回 None
+  fn working_directory(&self) -> Option<PathBuf> {
+    None
+  }
 }
@@ -0,0 +1,59 @@
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::{Column, Direction, Point};
+use alacritty_terminal::term::Term;
+use alacritty_terminal::term::search::{RegexIter, RegexSearch};
+
+/// 每个终端最多返回的匹配行数
+pub const MAX_MATCHES: usize = 200;
+
+/// 搜索匹配的行
+#[derive(Clone, Debug)]
+pub struct SearchMatch {
+  /// 自滚动历史顶部起的行号（与 `HighlightMark::row` 相同）
+  pub row: usize,
+  /// 匹配所在行的文本
+  pub text: String,
+}
+
+/// 在整个滚动历史和屏幕中搜索文本，每行只返回一次；查询中没有大写字母时不区分大小写
+pub fn search<T>(term: &Term<T>, query: &str) -> Vec<SearchMatch> {
+  let Ok(mut regex) = RegexSearch::new(&escape(query)) else {
+    return Vec::new();
+  };
+  let start = Point::new(term.topmost_line(), Column(0));
+  let end = Point::new(term.bottommost_line(), term.last_column());
+  let history_size = term.history_size() as i32;
+
+  let mut matches: Vec<SearchMatch> = Vec::new();
+  for found in RegexIter::new(start, end, Direction::Right, term, &mut regex) {
+    let line = found.start().line;
+    let row = (line.0 + history_size) as usize;
+    if matches.last().is_some_and(|last| last.row == row) {
+      continue;
+    }
+    let text = term.bounds_to_string(
+      Point::new(line, Column(0)),
+      Point::new(line, term.last_column()),
+    );
+    matches.push(SearchMatch {
+      row,
+      text: text.trim().to_string(),
+    });
+    if matches.len() >= MAX_MATCHES {
+      break;
+    }
+  }
+  matches
+}
+
+/// 转义正则元字符，按字面搜索
+fn escape(query: &str) -> String {
+  let mut escaped = String::with_capacity(query.len());
+  for c in query.chars() {
+    if "\\.+*?()|[]{}^$".contains(c) {
+      escaped.push('\\');
+    }
+    escaped.push(c);
+  }
+  escaped
+}
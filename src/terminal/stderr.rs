@@ -0,0 +1,315 @@
+use std::borrow::Cow;
+use std::ffi::OsString;
+use std::io::{IsTerminal as _, Read, Write};
+use std::process::{Command, Stdio};
+
+/// 以 stderr 标记模式运行命令的命令行参数
+pub const FLAG: &str = "--tag-stderr";
+/// 终端支持 stderr 标记时设置的环境变量
+const MARKERS_ENV: &str = "CATUS_STDERR_MARKERS";
+/// 本程序的路径，方便在终端中调用 `"$CATUS_BIN" --tag-stderr`
+const BIN_ENV: &str = "CATUS_BIN";
+
+/// 标记 OSC 序列的前缀（私有编号，其他终端会忽略）
+const MARKER_PREFIX: &str = "\x1b]7719;";
+const STDERR_MARKER: &str = "\x1b]7719;stderr\x07";
+const STDOUT_MARKER: &str = "\x1b]7719;stdout\x07";
+
+/// stderr 文本的背景色
+const TINT: &str = "\x1b[48;2;58;30;30m";
+const UNTINT: &str = "\x1b[49m";
+
+/// 跨块缓存的未完成转义序列的最大长度，超过后按普通输出处理
+const MAX_PENDING: usize = 64;
+
+/// 新建终端时附加的环境变量，`enabled` 为设置中的开关
+pub fn terminal_env(enabled: bool) -> Vec<(String, OsString)> {
+  if !enabled {
+    return Vec::new();
+  }
+  let mut env = vec![(MARKERS_ENV.into(), "1".into())];
+  if let Ok(exe) = std::env::current_exe() {
+    env.push((BIN_ENV.into(), exe.into()));
+  }
+  env
+}
+
+/// 转义序列的类型和长度
+enum Sequence {
+  /// 块结尾的不完整序列，等待下一块
+  Incomplete,
+  /// stderr 标记，`true` 表示进入 stderr
+  Marker(bool, usize),
+  /// SGR（`CSI … m`），会重置背景色
+  Sgr(usize),
+  Other(usize),
+}
+
+/// 将包装器输出的 stderr 标记转换为背景色
+///
+/// 包装器把子进程的 stderr 包在 `OSC 7719;stderr` 和 `OSC 7719;stdout` 之间写回终端。
+/// 这里在解析前去掉标记，并为其间的文本加上背景色；程序自己的 SGR 可能重置背景，
+/// 因此 stderr 区域内每个 SGR 之后都补上背景色。没有标记的输出原样返回。
+#[derive(Default)]
+pub struct StderrTinter {
+  in_stderr: bool,
+  pending: String,
+}
+
+impl StderrTinter {
+  pub fn reset(&mut self) {
+    *self = Self::default();
+  }
+
+  pub fn process<'a>(&mut self, text: &'a str) -> Cow<'a, str> {
+    if self.pending.is_empty() && !self.in_stderr && !may_contain_marker(text) {
+      return Cow::Borrowed(text);
+    }
+
+    let mut input = std::mem::take(&mut self.pending);
+    input.push_str(text);
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input.as_str();
+    while let Some(start) = rest.find('\x1b') {
+      output.push_str(&rest[..start]);
+      let sequence = &rest[start..];
+      let len = match classify(sequence) {
+        Sequence::Incomplete if sequence.len() <= MAX_PENDING => {
+          self.pending = sequence.to_string();
+          return Cow::Owned(output);
+        }
+        Sequence::Incomplete => 1,
+        Sequence::Marker(stderr, len) => {
+          if stderr != self.in_stderr {
+            output.push_str(if stderr { TINT } else { UNTINT });
+            self.in_stderr = stderr;
+          }
+          rest = &sequence[len..];
+          continue;
+        }
+        Sequence::Sgr(len) => {
+          output.push_str(&sequence[..len]);
+          if self.in_stderr {
+            output.push_str(TINT);
+          }
+          rest = &sequence[len..];
+          continue;
+        }
+        Sequence::Other(len) => len,
+      };
+      output.push_str(&sequence[..len]);
+      rest = &sequence[len..];
+    }
+    output.push_str(rest);
+    Cow::Owned(output)
+  }
+}
+
+/// 文本中是否有标记，或以标记的开头部分结尾（标记被块边界截断）
+fn may_contain_marker(text: &str) -> bool {
+  text.contains(MARKER_PREFIX)
+    || text
+      .rfind('\x1b')
+      .is_some_and(|start| MARKER_PREFIX.starts_with(&text[start..]))
+}
+
+/// 判断以 ESC 开头的序列
+fn classify(sequence: &str) -> Sequence {
+  let bytes = sequence.as_bytes();
+  match bytes.get(1) {
+    None => Sequence::Incomplete,
+    Some(b'[') => match bytes[2..].iter().position(|b| (0x40..=0x7e).contains(b)) {
+      Some(end) if bytes[2 + end] == b'm' => Sequence::Sgr(end + 3),
+      Some(end) => Sequence::Other(end + 3),
+      None => Sequence::Incomplete,
+    },
+    Some(b']') => {
+      if sequence.len() < MARKER_PREFIX.len() {
+        return if MARKER_PREFIX.starts_with(sequence) {
+          Sequence::Incomplete
+        } else {
+          Sequence::Other(2)
+        };
+      }
+      if sequence.starts_with(STDERR_MARKER) {
+        Sequence::Marker(true, STDERR_MARKER.len())
+      } else if sequence.starts_with(STDOUT_MARKER) {
+        Sequence::Marker(false, STDOUT_MARKER.len())
+      } else if sequence.starts_with(MARKER_PREFIX) && !sequence.contains('\x07') {
+        Sequence::Incomplete
+      } else {
+        Sequence::Other(2)
+      }
+    }
+    Some(_) => Sequence::Other(1),
+  }
+}
+
+/// 包装器模式：运行命令，将其 stderr 加上标记后写到终端
+///
+/// 不在启用了标记的 catus 终端中，或 stderr 被重定向（如 `2>file`）时直接运行命令，
+/// 避免标记写入文件或管道。返回命令的退出码。
+pub fn run(mut args: impl Iterator<Item = String>) -> i32 {
+  let Some(program) = args.next() else {
+    eprintln!("Usage: catus {} COMMAND [ARGS...]", FLAG);
+    return 2;
+  };
+  let mut command = Command::new(&program);
+  command.args(args);
+
+  let tag = std::env::var_os(MARKERS_ENV).is_some() && std::io::stderr().is_terminal();
+  if tag {
+    command.stderr(Stdio::piped());
+  }
+  let mut child = match command.spawn() {
+    Ok(child) => child,
+    Err(e) => {
+      eprintln!("Failed to run {}: {}", program, e);
+      return 127;
+    }
+  };
+
+  if let Some(mut stderr) = child.stderr.take() {
+    let mut out = std::io::stderr().lock();
+    let mut buffer = [0u8; 8192];
+    // 块边界可能截断 UTF-8 字符，留到下一块再写，避免标记插在字符中间
+    let mut carry = Vec::new();
+    loop {
+      let n = match stderr.read(&mut buffer) {
+        Ok(0) => break,
+        Ok(n) => n,
+        Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+        Err(_) => break,
+      };
+      carry.extend_from_slice(&buffer[..n]);
+      let valid = match std::str::from_utf8(&carry) {
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        _ => carry.len(),
+      };
+      if valid > 0 {
+        let _ = write_tagged(&mut out, &carry[..valid]);
+        carry.drain(..valid);
+      }
+    }
+    if !carry.is_empty() {
+      let _ = write_tagged(&mut out, &carry);
+    }
+  }
+
+  match child.wait() {
+    Ok(status) => exit_code(status),
+    Err(e) => {
+      eprintln!("Failed to wait for {}: {}", program, e);
+      1
+    }
+  }
+}
+
+fn write_tagged(out: &mut impl Write, data: &[u8]) -> std::io::Result<()> {
+  out.write_all(STDERR_MARKER.as_bytes())?;
+  out.write_all(data)?;
+  out.write_all(STDOUT_MARKER.as_bytes())?;
+  out.flush()
+}
+
+/// 被信号终止时按 shell 的惯例返回 128 + 信号编号
+fn exit_code(status: std::process::ExitStatus) -> i32 {
+  #[cfg(unix)]
+  {
+    use std::os::unix::process::ExitStatusExt as _;
+    if let Some(signal) = status.signal() {
+      return 128 + signal;
+    }
+  }
+  status.code().unwrap_or(1)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn tint(chunks: &[&str]) -> String {
+    let mut tinter = StderrTinter::default();
+    chunks.iter().map(|chunk| tinter.process(chunk)).collect()
+  }
+
+  #[test]
+  fn plain_output_is_borrowed() {
+    let mut tinter = StderrTinter::default();
+    assert!(matches!(tinter.process("hello\r\n"), Cow::Borrowed(_)));
+    assert!(matches!(
+      tinter.process("\x1b[1mbold\x1b[0m"),
+      Cow::Borrowed(_)
+    ));
+    assert!(matches!(
+      tinter.process("\x1b]0;title\x07"),
+      Cow::Borrowed(_)
+    ));
+  }
+
+  #[test]
+  fn markers_become_background() {
+    assert_eq!(
+      tint(&["out \x1b]7719;stderr\x07err\x1b]7719;stdout\x07 out"]),
+      format!("out {}err{} out", TINT, UNTINT)
+    );
+  }
+
+  #[test]
+  fn sgr_inside_stderr_keeps_background() {
+    assert_eq!(
+      tint(&["\x1b]7719;stderr\x07a\x1b[0mb\x1b]7719;stdout\x07"]),
+      format!("{}a\x1b[0m{}b{}", TINT, TINT, UNTINT)
+    );
+    // stderr 之外的 SGR 不变
+    assert_eq!(tint(&["\x1b[31mred\x1b[0m"]), "\x1b[31mred\x1b[0m");
+  }
+
+  #[test]
+  fn markers_split_across_chunks() {
+    let expected = format!("a{}b{}c", TINT, UNTINT);
+    let whole = "a\x1b]7719;stderr\x07b\x1b]7719;stdout\x07c";
+    for split in 1..whole.len() {
+      let (first, second) = whole.split_at(split);
+      assert_eq!(tint(&[first, second]), expected, "split at {}", split);
+    }
+  }
+
+  #[test]
+  fn sgr_split_across_chunks_inside_stderr() {
+    assert_eq!(
+      tint(&["\x1b]7719;stderr\x07a\x1b[3", "1mb"]),
+      format!("{}a\x1b[31m{}b", TINT, TINT)
+    );
+  }
+
+  #[test]
+  fn repeated_markers_are_dropped() {
+    assert_eq!(
+      tint(&["\x1b]7719;stdout\x07a\x1b]7719;stderr\x07\x1b]7719;stderr\x07b"]),
+      format!("a{}b", TINT)
+    );
+  }
+
+  #[test]
+  fn other_osc_passes_through() {
+    assert_eq!(
+      tint(&["\x1b]7719;stderr\x07\x1b]0;title\x07x"]),
+      format!("{}\x1b]0;title\x07x", TINT)
+    );
+  }
+
+  #[test]
+  fn overlong_pending_sequence_is_flushed() {
+    let long = format!("\x1b]7719;{}", "x".repeat(MAX_PENDING));
+    assert_eq!(tint(&[&long]), long);
+  }
+
+  #[test]
+  fn reset_leaves_stderr() {
+    let mut tinter = StderrTinter::default();
+    tinter.process("\x1b]7719;stderr\x07");
+    tinter.reset();
+    assert!(matches!(tinter.process("plain"), Cow::Borrowed(_)));
+  }
+}
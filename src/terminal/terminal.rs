@@ -1,26 +1,60 @@
+use crate::stats::Metric;
+use crate::stats::session_stats::{CommandRecord, PendingStats};
 use crate::terminal::content::{
-  IndexedCell, TerminalBounds, TerminalContent, TerminalEvent, TerminalPoint,
-  renderable_cursor_to_state,
+  IndexedCell, SelectionType, TerminalBounds, TerminalContent, TerminalEvent, TerminalPoint,
+  default_palette_rgb, renderable_cursor_to_state,
 };
+use crate::terminal::encoding::{DecodeStatus, OutputDecoder, TerminalEncoding};
+use crate::terminal::flood::BinaryFloodDetector;
+use crate::terminal::highlight::{HighlightRule, HighlightScanner};
+use crate::terminal::host;
 use crate::terminal::input::TerminalInput;
-use crate::terminal::pty::{Pty, TerminalSize};
+use crate::terminal::input_history::InputHistory;
+use crate::terminal::keys::{BackarrowModeScanner, BackspaceKey};
+use crate::terminal::overflow::GridOverflowDetector;
+use crate::terminal::pty::{LocalModes, Pty, TerminalSize};
+use crate::terminal::search::{self, SearchMatch};
+use crate::terminal::stderr::StderrTinter;
+use crate::terminal::unseen::UnseenOutput;
+use crate::terminal::untrusted::UntrustedFilter;
 use alacritty_terminal::event::EventListener;
 use alacritty_terminal::grid::Dimensions;
-use alacritty_terminal::term::{Config, Term, TermMode};
-use alacritty_terminal::vte::ansi::Processor;
+use alacritty_terminal::index::{Column, Direction, Line, Point as AlacPoint, Side};
+use alacritty_terminal::selection::Selection;
+use alacritty_terminal::term::color::COUNT as COLOR_COUNT;
+use alacritty_terminal::term::{Config, Osc52, Term, TermMode};
+use alacritty_terminal::vte::ansi::{Handler, NamedColor, NamedPrivateMode, Processor, Rgb};
 use gpui::*;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::VecDeque;
-use std::sync::Arc;
-use tokio::sync::{mpsc, watch};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc, oneshot, watch};
 
 /// 默认滚动历史行数
-const DEFAULT_SCROLL_HISTORY_LINES: usize = 10_000;
+pub(crate) const DEFAULT_SCROLL_HISTORY_LINES: usize = 10_000;
 /// 最大滚动历史行数
 pub const MAX_SCROLL_HISTORY_LINES: usize = 100_000;
 
+/// 创建终端时的选项
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TerminalOptions {
+  /// 不保留滚动历史，并禁止复制（用于敏感会话）
+  pub no_scrollback: bool,
+  /// 注入 shell 的环境变量集名称（见设置中的 `environments`）
+  pub environment: Option<String>,
+  /// 不受信任的输出：禁用链接、内联图片、OSC 52 剪贴板、标题修改等由输出触发的功能
+  pub untrusted: bool,
+  /// 启动时开启了 stderr 着色（设置中的 `stderr_tint`），只有这时才解析 stderr 标记；
+  /// 每次启动时按当前设置填写，不保存到会话
+  #[serde(skip)]
+  pub stderr_tint: bool,
+}
+
 /// 终端尺寸结构，用于 alacritty 的 Dimensions trait
 #[derive(Clone, Copy, Debug)]
-struct TermDimensions {
+pub(crate) struct TermDimensions {
   columns: usize,
   screen_lines: usize,
 }
@@ -57,6 +91,264 @@ impl From<TerminalSize> for TermDimensions {
   }
 }
 
+/// 动态颜色（前景、背景、光标）在调色板中的索引
+const DYNAMIC_COLOR_INDICES: [usize; 3] = [
+  NamedColor::Foreground as usize,
+  NamedColor::Background as usize,
+  NamedColor::Cursor as usize,
+];
+
+/// 进入备用屏幕时保存的动态颜色，退出备用屏幕时恢复，
+/// 避免全屏程序通过 OSC 10/11/12 修改的颜色在退出后残留
+#[derive(Default)]
+struct SavedDynamicColors(Option<[Option<Rgb>; 3]>);
+
+impl SavedDynamicColors {
+  /// 处理 PTY 数据后调用，根据备用屏幕的切换保存或恢复颜色
+  fn track(&mut self, was_alt_screen: bool, term: &mut Term<ChannelEventListener>) {
+    let is_alt_screen = term.mode().contains(TermMode::ALT_SCREEN);
+    match (was_alt_screen, is_alt_screen) {
+      (false, true) => {
+        self.0 = Some(DYNAMIC_COLOR_INDICES.map(|index| term.colors()[index]));
+      }
+      (true, false) => {
+        let Some(saved) = self.0.take() else {
+          return;
+        };
+        for (index, color) in DYNAMIC_COLOR_INDICES.into_iter().zip(saved) {
+          match color {
+            Some(rgb) => term.set_color(index, rgb),
+            None => term.reset_color(index),
+          }
+        }
+      }
+      _ => {}
+    }
+  }
+}
+
+/// 后台任务处理 PTY 输出的状态：解码器、各个扫描器和解析器，以及与 UI 共享的结果
+struct PipelineState {
+  term: Arc<async_lock::Mutex<Term<ChannelEventListener>>>,
+  pty: Arc<dyn Pty>,
+  /// 向 UI 发送最新内容
+  content_tx: watch::Sender<TerminalContent>,
+  parser: Processor<alacritty_terminal::vte::ansi::StdSyncHandler>,
+  highlights: HighlightScanner,
+  saved_colors: SavedDynamicColors,
+  decoder: OutputDecoder,
+  overflow: GridOverflowDetector,
+  flood: BinaryFloodDetector,
+  unseen: UnseenOutput,
+  /// 启动时没有开启 stderr 着色时为 None，输出中的标记原样交给解析器（作为未知 OSC 忽略）
+  stderr: Option<StderrTinter>,
+  untrusted: UntrustedFilter,
+  backarrow: BackarrowModeScanner,
+  /// 累计的输出字节数和执行的命令，由 UI 任务取走并计入会话统计
+  stats: Arc<PendingStats>,
+  /// 私密终端：不记录命令所在的主机和目录
+  private: bool,
+  /// 通过 ssh 连接的主机
+  remote_host: Arc<Mutex<Option<String>>>,
+  /// 上次检查的前台进程组，以及能否读取它的命令行
+  foreground: Option<(u32, bool)>,
+  /// 应用通过 DECBKM 设置的 Backspace 模式，供 UI 读取
+  backarrow_mode: Arc<Mutex<Option<BackspaceKey>>>,
+}
+
+impl PipelineState {
+  fn new(
+    term: Arc<async_lock::Mutex<Term<ChannelEventListener>>>,
+    pty: Arc<dyn Pty>,
+    content_tx: watch::Sender<TerminalContent>,
+    options: &TerminalOptions,
+    stats: Arc<PendingStats>,
+    remote_host: Arc<Mutex<Option<String>>>,
+    backarrow_mode: Arc<Mutex<Option<BackspaceKey>>>,
+  ) -> Self {
+    Self {
+      term,
+      pty,
+      content_tx,
+      parser: Processor::new(),
+      highlights: HighlightScanner::new(&HighlightRule::defaults()),
+      saved_colors: SavedDynamicColors::default(),
+      decoder: OutputDecoder::new(TerminalEncoding::default()),
+      overflow: GridOverflowDetector::default(),
+      flood: BinaryFloodDetector::default(),
+      unseen: UnseenOutput::default(),
+      stderr: options.stderr_tint.then(StderrTinter::default),
+      untrusted: UntrustedFilter::new(options.untrusted),
+      backarrow: BackarrowModeScanner::default(),
+      stats,
+      private: options.no_scrollback,
+      remote_host,
+      foreground: None,
+      backarrow_mode,
+    }
+  }
+
+  /// 处理一段 PTY 输出
+  ///
+  /// 依次经过：解码 → 二进制输出检测 → DECBKM → 过滤不受信任的序列 → ssh 主机跟踪 →
+  /// stderr 着色 → 解析 → 统计 → 网格溢出检测 → 高亮
+  async fn process_output(&mut self, data: &[u8]) {
+    self.stats.add_output(data.len() as u64);
+    let term = self.term.clone();
+    let mut term = term.lock().await;
+    let was_alt_screen = term.mode().contains(TermMode::ALT_SCREEN);
+
+    let replaced = self.decoder.status().replaced;
+    let text = self.decoder.decode(data);
+    self
+      .flood
+      .scan(data, self.decoder.status().replaced - replaced);
+    self.track_backarrow_mode(data);
+    let text = self.untrusted.process(&text);
+    self.track_remote_host(&text);
+    let text = match &mut self.stderr {
+      Some(stderr) => stderr.process(&text),
+      None => Cow::Borrowed(&*text),
+    };
+
+    self.unseen.begin(&term);
+    self.parser.advance(&mut *term, text.as_bytes());
+    self.unseen.finish(&term);
+    Metric::BytesParsed.record(data.len() as u64);
+
+    self.saved_colors.track(was_alt_screen, &mut term);
+    self.check_overflow(text.as_bytes(), &term);
+    self.highlights.refresh(&term, false);
+  }
+
+  /// 重置终端和所有扫描器的状态
+  async fn reset(&mut self, keep_scrollback: bool) {
+    let term = self.term.clone();
+    let mut term = term.lock().await;
+    Terminal::reset_term(&mut term, keep_scrollback);
+    self.parser = Processor::new();
+    self.saved_colors = SavedDynamicColors::default();
+    self.overflow.reset();
+    self.flood.reset();
+    if let Some(stderr) = &mut self.stderr {
+      stderr.reset();
+    }
+    self.untrusted.reset();
+    self.backarrow.reset();
+    self.track_backarrow_mode(&[]);
+    self.highlights.refresh(&term, true);
+  }
+
+  /// 从 Term 生成最新的 TerminalContent 并发送给 UI
+  async fn publish(&mut self) {
+    let term = self.term.lock().await;
+    let mut content = Terminal::build_content(
+      &term,
+      &self.highlights,
+      self.decoder.status(),
+      self.overflow.overflow(),
+      self.flood.is_flooding(),
+    );
+    drop(term);
+    content.unseen_lines = self.unseen.lines(content.display_offset);
+    let _ = self.content_tx.send(content);
+  }
+
+  /// 扫描输出中的 DECBKM，模式变化时更新 UI 读取的值
+  fn track_backarrow_mode(&mut self, data: &[u8]) {
+    self.backarrow.scan(data);
+    if let Ok(mut mode) = self.backarrow_mode.lock()
+      && *mode != self.backarrow.mode()
+    {
+      *mode = self.backarrow.mode();
+    }
+  }
+
+  /// 从输入的命令行识别 ssh 的目标主机；能读取前台进程的命令行时以前台进程为准，不使用输入的行
+  fn track_input_lines(&self, lines: &[String]) {
+    if self.foreground.is_some_and(|(_, readable)| readable) {
+      return;
+    }
+    if let Some(host) = lines
+      .iter()
+      .rev()
+      .find_map(|line| host::ssh_destination(line))
+    {
+      self.set_remote_host(Some(host));
+    }
+  }
+
+  /// 记录执行的命令，连同当时所在的 ssh 主机或本地工作目录
+  fn track_commands(&self, count: usize) {
+    if count == 0 {
+      return;
+    }
+    let record = if self.private {
+      CommandRecord::default()
+    } else {
+      let host = self.remote_host.lock().ok().and_then(|host| host.clone());
+      let directory = match host {
+        Some(_) => None,
+        None => self
+          .pty
+          .working_directory()
+          .map(|directory| directory.display().to_string()),
+      };
+      CommandRecord { host, directory }
+    };
+    self.stats.add_commands(record, count);
+  }
+
+  /// 跟踪通过 ssh 连接的主机
+  ///
+  /// 能读取前台进程的命令行时（Linux）以前台进程为准：前台是 ssh 时取它的目标主机，
+  /// 包括从历史中调出或 Tab 补全的命令；前台进程离开 ssh 后清除。
+  /// 否则只在行首出现 ssh 客户端自己的断开消息时清除，远程命令的输出不会误清除
+  fn track_remote_host(&mut self, text: &str) {
+    let Some(pgid) = self.pty.foreground_process() else {
+      self.foreground = None;
+      self.track_disconnect(text);
+      return;
+    };
+    if self.foreground.is_none_or(|(cached, _)| cached != pgid) {
+      let command = host::command_line(pgid);
+      if let Some(command) = &command {
+        self.set_remote_host(host::ssh_command_destination(command));
+      }
+      self.foreground = Some((pgid, command.is_some()));
+    }
+    if self.foreground.is_some_and(|(_, readable)| !readable) {
+      self.track_disconnect(text);
+    }
+  }
+
+  /// ssh 断开后清除识别到的主机
+  fn track_disconnect(&self, text: &str) {
+    if host::is_disconnect(text) {
+      self.set_remote_host(None);
+    }
+  }
+
+  fn set_remote_host(&self, host: Option<String>) {
+    if let Ok(mut remote_host) = self.remote_host.lock()
+      && *remote_host != host
+    {
+      *remote_host = host;
+    }
+  }
+
+  /// 检查全屏应用是否在网格之外定位光标；只在备用屏幕中检查
+  fn check_overflow(&mut self, data: &[u8], term: &Term<ChannelEventListener>) {
+    if term.mode().contains(TermMode::ALT_SCREEN) {
+      self
+        .overflow
+        .scan(data, term.screen_lines(), term.columns());
+    } else {
+      self.overflow.reset();
+    }
+  }
+}
+
 /// 内部事件（类似 Zed 的 InternalEvent）
 #[derive(Clone, Debug)]
 enum InternalEvent {
@@ -65,9 +357,11 @@ enum InternalEvent {
   /// 滚动
   Scroll(alacritty_terminal::grid::Scroll),
   /// 设置选区
-  SetSelection(Option<alacritty_terminal::selection::Selection>),
-  /// 更新选区
-  UpdateSelection(TerminalPoint),
+  SetSelection(Option<Selection>),
+  /// 跳转到上一个/下一个高亮匹配
+  JumpToHighlight(Direction),
+  /// 更新选区（网格坐标）
+  UpdateSelection(TerminalPoint, Side),
   /// 清除屏幕
   Clear,
   /// 复制选区
@@ -111,6 +405,16 @@ pub struct Terminal {
   term: Arc<async_lock::Mutex<Term<ChannelEventListener>>>,
   /// 终端配置
   term_config: Config,
+  /// 创建时的选项
+  options: TerminalOptions,
+  /// 发送到 PTY 的输入行历史
+  input_history: Arc<Mutex<InputHistory>>,
+  /// 通过 ssh 连接的主机
+  remote_host: Arc<Mutex<Option<String>>>,
+  /// 应用通过 DECBKM 设置的 Backspace 模式
+  backarrow_mode: Arc<Mutex<Option<BackspaceKey>>>,
+  /// shell 的进程 ID
+  process_id: Option<u32>,
   /// 内部事件队列（类似 Zed 的 events）
   events: VecDeque<InternalEvent>,
   /// 后台任务相关
@@ -123,6 +427,12 @@ pub struct Terminal {
   title: String,
   /// 鼠标模式状态
   mouse_mode: bool,
+  /// 最近一次绘制时的位置与单元格尺寸，用于将鼠标坐标转换为网格坐标
+  layout_bounds: Option<TerminalBounds>,
+  /// 最近一次请求的行列数，避免重复调整大小
+  requested_grid: Option<(usize, usize)>,
+  /// 网格比视图宽时，视图左边缘对应的列（横向平移的位置）
+  pan_column: usize,
 }
 
 impl Terminal {
@@ -130,15 +440,29 @@ impl Terminal {
   ///
   /// # Arguments
   /// * `pty` - PTY 实现
+  /// * `options` - 终端选项
   /// * `cx` - GPUI Context
-  pub fn new(pty: Box<dyn Pty>, cx: &mut Context<Self>) -> anyhow::Result<Self> {
+  pub fn new(
+    pty: Box<dyn Pty>,
+    options: TerminalOptions,
+    cx: &mut Context<Self>,
+  ) -> anyhow::Result<Self> {
     // 创建初始尺寸
     let initial_size = TerminalSize::default_size();
     let term_dimensions = TermDimensions::from(initial_size);
 
-    // 创建终端配置
+    // 创建终端配置，敏感会话不保留滚动历史，不受信任的输出不能读写剪贴板
     let term_config = Config {
-      scrolling_history: DEFAULT_SCROLL_HISTORY_LINES,
+      scrolling_history: if options.no_scrollback {
+        0
+      } else {
+        DEFAULT_SCROLL_HISTORY_LINES
+      },
+      osc52: if options.untrusted {
+        Osc52::Disabled
+      } else {
+        Osc52::default()
+      },
       ..Config::default()
     };
 
@@ -162,24 +486,69 @@ impl Terminal {
     // 克隆用于后台任务的 Arc
     let term_for_task = term.clone();
 
+    // 后台任务累计的输出字节数和执行的命令，由 UI 任务取走并计入会话统计
+    let pending_stats = Arc::new(PendingStats::default());
+
+    // 输入行历史由后台任务在写入 PTY 时记录，私密终端不记录
+    let input_history = Arc::new(Mutex::new(InputHistory::default()));
+    let input_history_for_task = if options.no_scrollback {
+      Arc::new(Mutex::new(InputHistory::untracked()))
+    } else {
+      input_history.clone()
+    };
+
+    // 通过 ssh 连接的主机，由后台任务从前台进程或输入的命令识别
+    let remote_host = Arc::new(Mutex::new(None::<String>));
+
+    // 应用通过 DECBKM 设置的 Backspace 模式
+    let backarrow_mode = Arc::new(Mutex::new(None::<BackspaceKey>));
+
     // 获取实体句柄（用于后台任务更新内容）
     let entity = cx.entity().clone();
 
+    // 后台任务处理 PTY 输出的状态
+    let process_id = pty.process_id();
+    let pty: Arc<dyn Pty> = Arc::from(pty);
+    let pipeline = PipelineState::new(
+      term.clone(),
+      pty.clone(),
+      content_tx,
+      &options,
+      pending_stats.clone(),
+      remote_host.clone(),
+      backarrow_mode.clone(),
+    );
+
     // 启动后台任务处理 PTY 和终端事件
+    let executor = cx.background_executor().clone();
     let background_task = cx.background_spawn(async move {
       let pty = pty;
       let term = term_for_task;
-      let mut parser = Processor::<alacritty_terminal::vte::ansi::StdSyncHandler>::new();
+      let input_history = input_history_for_task;
+      let mut pipeline = pipeline;
+      let mut pty_size = initial_size;
 
       // 启动 PTY 读取器
       let pty_reader = pty.start_reader();
 
       loop {
+        let highlight_delay = pipeline.highlights.pending_delay();
         tokio::select! {
             // 处理来自 UI 的输入
             Some(input) = input_rx.recv() => {
                 match input {
                     TerminalInput::Write(data) => {
+                        let hidden = pty.local_modes().is_some_and(LocalModes::hides_input);
+                        let (lines, submitted) = input_history
+                            .lock()
+                            .map(|mut history| {
+                                let lines = history.record(&data, hidden);
+                                (lines, history.take_submitted())
+                            })
+                            .unwrap_or_default();
+                        pipeline.track_input_lines(&lines);
+                        pipeline.track_commands(submitted);
+                        let data = pipeline.decoder.status().encoding.encode(&data);
                         if let Err(e) = pty.write(&data) {
                             eprintln!("PTY write error: {}", e);
                         }
@@ -188,27 +557,79 @@ impl Terminal {
                         let dims = TermDimensions::from(size);
                         let mut term_guard = term.lock().await;
                         term_guard.resize(dims);
+                        pipeline.highlights.refresh(&term_guard, true);
                         drop(term_guard);
                         if let Err(e) = pty.resize(size) {
                             eprintln!("PTY resize error: {}", e);
                         }
-                        // 强制同步内容
-                        let content = Self::make_content_sync(&term).await;
-                        let _ = content_tx.send(content);
+                        pty_size = size;
+                        pipeline.overflow.reset();
+                        pipeline.publish().await;
                     }
                     TerminalInput::PtyData(data) => {
-                        // 处理 PTY 数据
+                        pipeline.process_output(&data).await;
+                        pipeline.publish().await;
+                    }
+                    TerminalInput::Scroll(scroll) => {
+                        term.lock().await.scroll_display(scroll);
+                        pipeline.publish().await;
+                    }
+                    TerminalInput::JumpToHighlight(direction) => {
                         let mut term_guard = term.lock().await;
-                        parser.advance(&mut *term_guard, &data);
+                        pipeline.highlights.refresh(&term_guard, true);
+                        pipeline.highlights.jump(&mut term_guard, direction);
                         drop(term_guard);
-                        // 更新内容
-                        let content = Self::make_content_sync(&term).await;
-                        let _ = content_tx.send(content);
+                        pipeline.publish().await;
+                    }
+                    TerminalInput::SetSelection(selection) => {
+                        term.lock().await.selection = selection;
+                        pipeline.publish().await;
+                    }
+                    TerminalInput::UpdateSelection(point, side) => {
+                        if let Some(selection) = term.lock().await.selection.as_mut() {
+                            selection.update(point, side);
+                        }
+                        pipeline.publish().await;
+                    }
+                    TerminalInput::SelectionText(reply) => {
+                        let text = term.lock().await.selection_to_string();
+                        let _ = reply.send(text);
+                    }
+                    TerminalInput::Search(query, reply) => {
+                        let matches = search::search(&*term.lock().await, &query);
+                        let _ = reply.send(matches);
+                    }
+                    TerminalInput::RevealRow(row) => {
+                        let mut term_guard = term.lock().await;
+                        let line = Line(row as i32 - term_guard.history_size() as i32);
+                        let line = line.clamp(term_guard.topmost_line(), term_guard.bottommost_line());
+                        pipeline.highlights.reveal(&mut term_guard, line);
+                        drop(term_guard);
+                        pipeline.publish().await;
+                    }
+                    TerminalInput::SetEncoding(encoding) => {
+                        pipeline.decoder.set_encoding(encoding);
+                        pipeline.publish().await;
+                    }
+                    TerminalInput::Reset { keep_scrollback } => {
+                        pipeline.reset(keep_scrollback).await;
+                        pipeline.publish().await;
+                    }
+                    TerminalInput::Redraw => {
+                        // 尺寸不变时内核不会发送 SIGWINCH，先缩小一行再恢复
+                        let shrunk = TerminalSize {
+                            rows: pty_size.rows.saturating_sub(1).max(1),
+                            ..pty_size
+                        };
+                        if let Err(e) = pty.resize(shrunk).and_then(|_| pty.resize(pty_size)) {
+                            eprintln!("PTY resize error: {}", e);
+                        }
+                        pipeline.overflow.reset();
+                        pipeline.publish().await;
                     }
                     TerminalInput::Sync => {
                         // 强制同步内容
-                        let content = Self::make_content_sync(&term).await;
-                        let _ = content_tx.send(content);
+                        pipeline.publish().await;
                     }
                     TerminalInput::Shutdown => {
                         let _ = pty.close();
@@ -219,17 +640,21 @@ impl Terminal {
 
             // 处理 PTY 读取的数据
             Ok(data) = pty_reader.recv() => {
-                let mut term_guard = term.lock().await;
-                parser.advance(&mut *term_guard, &data);
+                pipeline.process_output(&data).await;
+                pipeline.publish().await;
+            }
+
+            // 输出停止后补上被节流跳过的高亮扫描
+            _ = executor.timer(highlight_delay.unwrap_or_default()), if highlight_delay.is_some() => {
+                let term_guard = term.lock().await;
+                pipeline.highlights.refresh(&term_guard, false);
                 drop(term_guard);
-                // 更新内容
-                let content = Self::make_content_sync(&term).await;
-                let _ = content_tx.send(content);
+                pipeline.publish().await;
             }
 
             // 处理 alacritty 事件
             Some(event) = events_rx.recv() => {
-                Self::process_alacritty_event(&event, &content_tx).await;
+                Self::process_alacritty_event(&event, pty.as_ref(), &term).await;
             }
 
             else => break,
@@ -251,6 +676,7 @@ impl Terminal {
         // 更新 Terminal 实体的 content 字段
         this.update(cx, |terminal, cx| {
           terminal.content = content;
+          pending_stats.flush(cx);
           cx.emit(TerminalEvent::Wakeup);
           cx.notify();
         });
@@ -263,6 +689,11 @@ impl Terminal {
       content,
       term,
       term_config,
+      options,
+      input_history,
+      remote_host,
+      backarrow_mode,
+      process_id,
       events: VecDeque::new(),
       tasks: Some(TerminalTasks {
         input_tx,
@@ -273,6 +704,9 @@ impl Terminal {
       selection_head: None,
       title: "Terminal".to_string(),
       mouse_mode: false,
+      layout_bounds: None,
+      requested_grid: None,
+      pan_column: 0,
     })
   }
 
@@ -281,19 +715,36 @@ impl Terminal {
     use crate::terminal::local_pty::LocalPty;
 
     let size = TerminalSize::default_size();
-    let pty = LocalPty::new(size, None)?;
+    let pty = LocalPty::new(size, None, &[])?;
 
-    Self::new(Box::new(pty), cx)
+    Self::new(Box::new(pty), TerminalOptions::default(), cx)
   }
 
   /// 处理 alacritty 事件（后台任务中调用）
   async fn process_alacritty_event(
     event: &alacritty_terminal::event::Event,
-    _content_tx: &watch::Sender<TerminalContent>,
+    pty: &dyn Pty,
+    term: &Arc<async_lock::Mutex<Term<ChannelEventListener>>>,
   ) {
     use alacritty_terminal::event::Event as AlacEvent;
 
     match event {
+      // 终端需要回写给应用的数据（如 DSR 光标位置报告）
+      AlacEvent::PtyWrite(text) => {
+        if let Err(e) = pty.write(text.as_bytes()) {
+          eprintln!("PTY write error: {}", e);
+        }
+      }
+      // OSC 4/10/11/12 颜色查询：回报实际使用的颜色，便于应用判断深色/浅色背景
+      AlacEvent::ColorRequest(index, formatter) => {
+        let [r, g, b] = term.lock().await.colors()[*index]
+          .map(|rgb| [rgb.r, rgb.g, rgb.b])
+          .unwrap_or_else(|| default_palette_rgb(*index));
+        let response = formatter(Rgb { r, g, b });
+        if let Err(e) = pty.write(response.as_bytes()) {
+          eprintln!("PTY write error: {}", e);
+        }
+      }
       AlacEvent::Title(_title) => {}
       AlacEvent::Wakeup => {}
       AlacEvent::Bell => {}
@@ -302,42 +753,72 @@ impl Terminal {
     }
   }
 
-  /// 从 Term 生成 TerminalContent（后台任务中调用）
-  async fn make_content_sync(
-    term: &Arc<async_lock::Mutex<Term<ChannelEventListener>>>,
+  /// 完全重置终端状态（RIS）：模式、字符集、制表位、颜色和屏幕内容
+  ///
+  /// 保留滚动历史时，先退出备用屏幕，把当前屏幕内容推入历史，重置后再恢复主屏幕网格
+  fn reset_term(term: &mut Term<ChannelEventListener>, keep_scrollback: bool) {
+    let saved_grid = keep_scrollback.then(|| {
+      if term.mode().contains(TermMode::ALT_SCREEN) {
+        term.unset_private_mode(NamedPrivateMode::SwapScreenAndSetRestoreCursor.into());
+      }
+      let mut grid = term.grid().clone();
+      grid.clear_viewport();
+      grid.cursor = Default::default();
+      grid.saved_cursor = Default::default();
+      grid
+    });
+
+    term.reset_state();
+    for index in 0..COLOR_COUNT {
+      term.reset_color(index);
+    }
+    if let Some(grid) = saved_grid {
+      *term.grid_mut() = grid;
+    }
+  }
+
+  /// 从 Term 生成 TerminalContent；不依赖窗口，基准测试也使用
+  pub fn build_content<T: EventListener>(
+    term: &Term<T>,
+    highlights: &HighlightScanner,
+    decode_status: DecodeStatus,
+    grid_overflow: Option<(usize, usize)>,
+    binary_flood: bool,
   ) -> TerminalContent {
-    let term_guard = term.lock().await;
-    let content = term_guard.renderable_content();
+    let content = term.renderable_content();
 
     let estimated_size = content.display_iter.size_hint().0;
     let mut cells = Vec::with_capacity(estimated_size);
 
+    // display_iter 返回的是网格行号（滚动到历史中时为负数），转换为视口行号
+    let display_offset = content.display_offset as i32;
     for indexed in content.display_iter {
       cells.push(IndexedCell {
         point: TerminalPoint {
-          line: indexed.point.line,
+          line: Line(indexed.point.line.0 + display_offset),
           column: indexed.point.column,
         },
         cell: indexed.cell.clone(),
       });
     }
 
-    let cursor_char = term_guard.grid()[content.cursor.point].c;
+    let cursor_char = term.grid()[content.cursor.point].c;
 
     let selection = content
       .selection
       .map(|range| crate::terminal::content::SelectionRange {
         start: TerminalPoint {
-          line: range.start.line,
+          line: range.start.line + display_offset,
           column: range.start.column,
         },
         end: TerminalPoint {
-          line: range.end.line,
+          line: range.end.line + display_offset,
           column: range.end.column,
         },
+        is_block: range.is_block,
       });
 
-    let scrolled_to_top = content.display_offset == term_guard.history_size();
+    let scrolled_to_top = content.display_offset == term.history_size();
     let scrolled_to_bottom = content.display_offset == 0;
 
     TerminalContent {
@@ -345,17 +826,25 @@ impl Terminal {
       mode: content.mode,
       display_offset: content.display_offset,
       selection,
-      cursor: renderable_cursor_to_state(&content.cursor),
+      cursor: renderable_cursor_to_state(&content.cursor, content.display_offset),
       cursor_char,
       terminal_bounds: TerminalBounds::new(
         px(8.0),
         px(16.0),
         Bounds::default(),
-        term_guard.screen_lines(),
-        term_guard.columns(),
+        term.screen_lines(),
+        term.columns(),
       ),
       scrolled_to_top,
       scrolled_to_bottom,
+      history_size: term.history_size(),
+      highlight_marks: highlights.marks().clone(),
+      active_highlight_row: highlights.active_row(term),
+      colors: *term.colors(),
+      decode_status,
+      grid_overflow,
+      binary_flood,
+      unseen_lines: 0,
       title: "Terminal".to_string(),
     }
   }
@@ -382,10 +871,32 @@ impl Terminal {
         }
         self.content.terminal_bounds = bounds;
       }
-      InternalEvent::Scroll(_scroll) => {}
-      InternalEvent::SetSelection(_selection) => {}
-      InternalEvent::UpdateSelection(point) => {
-        let _ = point;
+      InternalEvent::Scroll(scroll) => {
+        if let Some(tasks) = &self.tasks {
+          let _ = tasks.input_tx.try_send(TerminalInput::Scroll(scroll));
+        }
+      }
+      InternalEvent::JumpToHighlight(direction) => {
+        if let Some(tasks) = &self.tasks {
+          let _ = tasks
+            .input_tx
+            .try_send(TerminalInput::JumpToHighlight(direction));
+        }
+      }
+      InternalEvent::SetSelection(selection) => {
+        if let Some(tasks) = &self.tasks {
+          let _ = tasks
+            .input_tx
+            .try_send(TerminalInput::SetSelection(selection));
+        }
+      }
+      InternalEvent::UpdateSelection(point, side) => {
+        if let Some(tasks) = &self.tasks {
+          let point = AlacPoint::new(point.line, point.column);
+          let _ = tasks
+            .input_tx
+            .try_send(TerminalInput::UpdateSelection(point, side));
+        }
       }
       InternalEvent::Clear => {
         if let Some(tasks) = &self.tasks {
@@ -394,15 +905,9 @@ impl Terminal {
       }
       InternalEvent::Copy => {}
       InternalEvent::Paste(text) => {
-        let data = if self.content.mode.contains(TermMode::BRACKETED_PASTE) {
-          format!("\x1b[200~{}\x1b[201~", text.replace('\x1b', ""))
-        } else {
-          text.replace("\r\n", "\r").replace('\n', "\r")
-        };
+        let data = encode_paste(&text, self.content.mode);
         if let Some(tasks) = &self.tasks {
-          let _ = tasks
-            .input_tx
-            .try_send(TerminalInput::Write(data.into_bytes()));
+          let _ = tasks.input_tx.try_send(TerminalInput::Write(data));
         }
       }
     }
@@ -422,9 +927,74 @@ impl Terminal {
     }
   }
 
-  /// 调整终端大小
+  /// 将选中的文本作为命令执行：多行时逐行执行，末尾补回车
+  pub fn run_command(&mut self, text: &str) -> anyhow::Result<()> {
+    let command = text.trim_end().replace("\r\n", "\r").replace('\n', "\r");
+    self.input(format!("{}\r", command).into_bytes())
+  }
+
+  /// 以一次粘贴发送（可能多行的）文本并回车执行
+  ///
+  /// 开启括号粘贴的 shell 和 REPL 会把整段作为一次输入，不会逐行执行
+  pub fn submit(&mut self, text: &str, cx: &mut Context<Self>) -> anyhow::Result<()> {
+    self.paste(text);
+    // 先处理粘贴事件，保证回车在粘贴内容之后发送
+    self.sync(cx);
+    self.input(b"\r".to_vec())
+  }
+
+  /// 重置终端（RIS），用于二进制输出导致画面错乱后恢复
+  pub fn reset(&mut self, keep_scrollback: bool) {
+    if let Some(tasks) = &self.tasks {
+      let _ = tasks
+        .input_tx
+        .try_send(TerminalInput::Reset { keep_scrollback });
+    }
+  }
+
+  /// 中断二进制输出：发送 Ctrl+C 并重置终端（保留滚动历史）
+  pub fn stop_binary_flood(&mut self) {
+    let _ = self.input(vec![0x03]);
+    self.reset(true);
+  }
+
+  /// 重新发送窗口大小（SIGWINCH），让全屏应用按当前网格重绘
+  pub fn redraw(&mut self) {
+    if let Some(tasks) = &self.tasks {
+      let _ = tasks.input_tx.try_send(TerminalInput::Redraw);
+    }
+  }
+
+  /// 通知终端焦点变化
+  ///
+  /// 应用开启焦点报告（DECSET 1004）时发送 CSI I / CSI O，vim、tmux 等据此响应焦点变化
+  pub fn focus_changed(&mut self, focused: bool) {
+    if !self.content.mode.contains(TermMode::FOCUS_IN_OUT) {
+      return;
+    }
+    let data = if focused { b"\x1b[I" } else { b"\x1b[O" };
+    if let Some(tasks) = &self.tasks {
+      let _ = tasks.input_tx.try_send(TerminalInput::Write(data.to_vec()));
+    }
+  }
+
+  /// 切换 PTY 输入输出使用的字符编码，同时清空无效字节序列计数
+  pub fn set_encoding(&mut self, encoding: TerminalEncoding) {
+    if let Some(tasks) = &self.tasks {
+      let _ = tasks
+        .input_tx
+        .try_send(TerminalInput::SetEncoding(encoding));
+    }
+  }
+
+  /// 调整终端大小，行列数没有变化时忽略
   pub fn resize(&mut self, bounds: TerminalBounds) {
-    let _ = self.events.push_back(InternalEvent::Resize(bounds));
+    let grid = (bounds.num_lines(), bounds.num_columns());
+    if self.requested_grid == Some(grid) {
+      return;
+    }
+    self.requested_grid = Some(grid);
+    self.events.push_back(InternalEvent::Resize(bounds));
   }
 
   /// 滚动终端
@@ -468,6 +1038,20 @@ impl Terminal {
     self.scroll(Scroll::Bottom);
   }
 
+  /// 滚动到下一个高亮匹配（如 ERROR 行）
+  pub fn scroll_to_next_highlight(&mut self) {
+    self
+      .events
+      .push_back(InternalEvent::JumpToHighlight(Direction::Right));
+  }
+
+  /// 滚动到上一个高亮匹配
+  pub fn scroll_to_previous_highlight(&mut self) {
+    self
+      .events
+      .push_back(InternalEvent::JumpToHighlight(Direction::Left));
+  }
+
   /// 获取当前内容的引用
   pub fn content(&self) -> &TerminalContent {
     &self.content
@@ -500,8 +1084,166 @@ impl Terminal {
       .push_back(InternalEvent::Paste(text.to_string()));
   }
 
+  /// 记录最近一次绘制的布局（TerminalElement 在 prepaint 中调用）
+  pub fn set_layout_bounds(&mut self, bounds: TerminalBounds) {
+    self.layout_bounds = Some(bounds);
+    self.pan_column = self.pan_column.min(self.max_pan_column());
+  }
+
+  /// 按视图能容纳的列数计算网格列数
+  ///
+  /// 有选区时视图变窄不减少列数：列数变化会清除选区，保留原来的宽度让选区可以横向平移查看，
+  /// 选区清除后的下一次绘制再按视图宽度调整
+  pub fn columns_for_view(&self, view_columns: usize) -> usize {
+    match self.requested_grid {
+      Some((_, cols)) if self.has_selection() && cols > view_columns => cols,
+      _ => view_columns,
+    }
+  }
+
+  /// 视图左边缘对应的列
+  pub fn pan_column(&self) -> usize {
+    self.pan_column
+  }
+
+  /// 横向平移的最大位置，网格不比视图宽时为 0
+  fn max_pan_column(&self) -> usize {
+    let Some(layout) = self.layout_bounds else {
+      return 0;
+    };
+    let visible = (layout.bounds.size.width / layout.cell_width).floor() as usize;
+    layout.num_columns().saturating_sub(visible.max(1))
+  }
+
+  /// 横向平移指定的列数（负数向左），返回位置是否变化
+  pub fn pan(&mut self, columns: i32) -> bool {
+    let pan_column = self
+      .pan_column
+      .saturating_add_signed(columns as isize)
+      .min(self.max_pan_column());
+    let changed = pan_column != self.pan_column;
+    self.pan_column = pan_column;
+    changed
+  }
+
+  /// 最近一次绘制的布局
+  pub fn layout_bounds(&self) -> Option<TerminalBounds> {
+    self.layout_bounds
+  }
+
+  /// 最近一次请求的列数和行数
+  pub fn grid_size(&self) -> Option<(usize, usize)> {
+    self.requested_grid.map(|(rows, cols)| (cols, rows))
+  }
+
+  /// 将窗口坐标转换为网格坐标及所在单元格的左右半边
+  pub fn grid_point(&self, position: Point<Pixels>) -> Option<(TerminalPoint, Side)> {
+    let layout = self.layout_bounds?;
+    let num_lines = self.content.terminal_bounds.num_lines();
+    let num_columns = self.content.terminal_bounds.num_columns();
+    if num_lines == 0 || num_columns == 0 {
+      return None;
+    }
+
+    let x =
+      ((position.x - layout.bounds.origin.x) / layout.cell_width).max(0.) + self.pan_column as f32;
+    let y = ((position.y - layout.bounds.origin.y) / layout.line_height).max(0.);
+    let column = (x as usize).min(num_columns - 1);
+    let side = if x as usize >= num_columns || x.fract() >= 0.5 {
+      Side::Right
+    } else {
+      Side::Left
+    };
+    let row = (y as usize).min(num_lines - 1) as i32;
+
+    Some((
+      TerminalPoint {
+        line: Line(row - self.content.display_offset as i32),
+        column: Column(column),
+      },
+      side,
+    ))
+  }
+
+  /// 在指定位置（网格坐标）开始新的选区
+  pub fn start_selection(&mut self, ty: SelectionType, point: TerminalPoint, side: Side) {
+    let selection = Selection::new(ty.into(), AlacPoint::new(point.line, point.column), side);
+    self.set_selection(Some(selection));
+  }
+
+  /// 将选区扩展到指定位置（网格坐标）
+  pub fn update_selection(&mut self, point: TerminalPoint, side: Side) {
+    self
+      .events
+      .push_back(InternalEvent::UpdateSelection(point, side));
+  }
+
+  pub fn options(&self) -> &TerminalOptions {
+    &self.options
+  }
+
+  /// 输入行历史，最新的在前
+  pub fn input_history(&self) -> Vec<String> {
+    self
+      .input_history
+      .lock()
+      .map(|history| history.lines().cloned().collect())
+      .unwrap_or_default()
+  }
+
+  /// shell 的进程 ID，也是终端会话的 ID
+  pub fn process_id(&self) -> Option<u32> {
+    self.process_id
+  }
+
+  /// 在终端中通过 ssh 连接的主机
+  pub fn remote_host(&self) -> Option<String> {
+    self.remote_host.lock().ok().and_then(|host| host.clone())
+  }
+
+  /// 应用设置的 Backspace 模式，没有设置过时为 None
+  pub fn backarrow_mode(&self) -> Option<BackspaceKey> {
+    self.backarrow_mode.lock().ok().and_then(|mode| *mode)
+  }
+
+  /// 是否有选区
+  pub fn has_selection(&self) -> bool {
+    self.content.selection.is_some()
+  }
+
+  /// 在后台任务中提取选中的文本
+  ///
+  /// 选区可能覆盖几十万字符的超长行，只在需要时提取，避免每次刷新内容都复制一遍
+  pub fn selection_text(&self) -> impl Future<Output = Option<String>> + use<> {
+    let (reply_tx, reply_rx) = oneshot::channel();
+    if let Some(tasks) = &self.tasks {
+      let _ = tasks
+        .input_tx
+        .try_send(TerminalInput::SelectionText(reply_tx));
+    }
+    async move { reply_rx.await.ok().flatten() }
+  }
+
+  /// 在后台搜索滚动历史和屏幕
+  pub fn search(&self, query: &str) -> impl Future<Output = Vec<SearchMatch>> + use<> {
+    let (reply_tx, reply_rx) = oneshot::channel();
+    if let Some(tasks) = &self.tasks {
+      let _ = tasks
+        .input_tx
+        .try_send(TerminalInput::Search(query.to_string(), reply_tx));
+    }
+    async move { reply_rx.await.unwrap_or_default() }
+  }
+
+  /// 滚动到搜索匹配的行并高亮显示
+  pub fn reveal_row(&mut self, row: usize) {
+    if let Some(tasks) = &self.tasks {
+      let _ = tasks.input_tx.try_send(TerminalInput::RevealRow(row));
+    }
+  }
+
   /// 设置选区
-  pub fn set_selection(&mut self, selection: Option<alacritty_terminal::selection::Selection>) {
+  pub fn set_selection(&mut self, selection: Option<Selection>) {
     self
       .events
       .push_back(InternalEvent::SetSelection(selection));
@@ -514,3 +1256,46 @@ impl Terminal {
 }
 
 impl EventEmitter<TerminalEvent> for Terminal {}
+
+/// 编码粘贴的文本
+///
+/// 应用开启 bracketed paste（DECSET 2004）时包裹在 `ESC[200~`/`ESC[201~` 之间，
+/// 并去掉文本中的 ESC，使粘贴内容无法提前结束包裹；否则把换行转换为回车
+fn encode_paste(text: &str, mode: TermMode) -> Vec<u8> {
+  if mode.contains(TermMode::BRACKETED_PASTE) {
+    format!("\x1b[200~{}\x1b[201~", text.replace('\x1b', "")).into_bytes()
+  } else {
+    text.replace("\r\n", "\r").replace('\n', "\r").into_bytes()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use alacritty_terminal::term::TermMode;
+
+  use super::encode_paste;
+
+  #[test]
+  fn paste_without_bracketed_mode_sends_returns() {
+    assert_eq!(
+      encode_paste("echo a\r\necho b\n", TermMode::empty()),
+      b"echo a\recho b\r"
+    );
+  }
+
+  #[test]
+  fn paste_in_bracketed_mode_is_wrapped() {
+    assert_eq!(
+      encode_paste("echo a\necho b", TermMode::BRACKETED_PASTE),
+      b"\x1b[200~echo a\necho b\x1b[201~"
+    );
+  }
+
+  #[test]
+  fn bracketed_paste_cannot_end_early() {
+    assert_eq!(
+      encode_paste("a\x1b[201~rm -rf ~\r", TermMode::BRACKETED_PASTE),
+      b"\x1b[200~a[201~rm -rf ~\r\x1b[201~"
+    );
+  }
+}
@@ -1,9 +1,15 @@
-use crate::terminal::content::{ansi_color_to_rgb, rgb_to_hsla, TerminalContent};
+use crate::stats::Metric;
+use crate::terminal::content::{TerminalBounds, TerminalContent, rgb_to_hsla};
 use crate::terminal::terminal::Terminal;
 use alacritty_terminal::term::cell::Flags;
 use gpui::*;
 use std::mem;
 
+/// 默认字号
+pub const DEFAULT_FONT_SIZE: Pixels = px(14.);
+/// 行高与字号之比
+const LINE_HEIGHT_RATIO: f32 = 1.2;
+
 /// 终端元素布局状态
 pub struct LayoutState {
   bounds: Bounds<Pixels>,
@@ -12,6 +18,8 @@ pub struct LayoutState {
   char_height: Pixels,
   background_color: Hsla,
   cursor_visible: bool,
+  /// 视图左边缘对应的列
+  pan_column: usize,
 }
 
 /// 批处理的文本运行（类似 Zed 的 BatchedTextRun）
@@ -49,12 +57,31 @@ impl BatchedTextRun {
   }
 }
 
+/// 单元格的背景色，以及当前的默认背景色（应用可通过 OSC 11 修改）
+#[derive(Clone, Copy)]
+struct CellBackground {
+  color: [u8; 3],
+  default: [u8; 3],
+}
+
+impl CellBackground {
+  /// 与默认背景相同的单元格由整体背景填充绘制，不需要单独绘制
+  fn is_default(&self) -> bool {
+    self.color == self.default
+  }
+}
+
 /// 自定义 Terminal Element，使用 paint 方式渲染终端内容
 pub struct TerminalElement {
   terminal: Entity<Terminal>,
   content: TerminalContent,
   char_width: Pixels,
   char_height: Pixels,
+  font_size: Pixels,
+  /// 在这些列的左边缘绘制竖直参考线
+  column_guides: Vec<usize>,
+  /// 叠加在默认背景上的色调
+  background_tint: Option<[u8; 3]>,
   focus_handle: FocusHandle,
 }
 
@@ -69,10 +96,31 @@ impl TerminalElement {
       content: initial_content,
       char_width: px(8.),
       char_height: px(16.),
+      font_size: DEFAULT_FONT_SIZE,
+      column_guides: Vec::new(),
+      background_tint: None,
       focus_handle,
     }
   }
 
+  /// 设置字号（每个终端视图可以独立缩放）
+  pub fn font_size(mut self, font_size: Pixels) -> Self {
+    self.font_size = font_size;
+    self
+  }
+
+  /// 设置列参考线（如 80、100、120 列）
+  pub fn column_guides(mut self, columns: Vec<usize>) -> Self {
+    self.column_guides = columns;
+    self
+  }
+
+  /// 设置背景色调（如按连接的主机区分环境）
+  pub fn background_tint(mut self, tint: Option<[u8; 3]>) -> Self {
+    self.background_tint = tint;
+    self
+  }
+
   /// 创建终端字体
   fn create_font() -> Font {
     Font {
@@ -88,11 +136,11 @@ impl TerminalElement {
   fn calculate_char_dimensions(&mut self, window: &mut Window) {
     let font = Self::create_font();
     let font_id = window.text_system().resolve_font(&font);
-    if let Ok(advance) = window.text_system().advance(font_id, px(14.), 'm') {
+    if let Ok(advance) = window.text_system().advance(font_id, self.font_size, 'm') {
       self.char_width = advance.width;
     }
     // 行高通常是字体大小的 1.2 倍左右
-    self.char_height = px(14. * 1.2);
+    self.char_height = self.font_size * LINE_HEIGHT_RATIO;
   }
 
   /// 创建文本运行
@@ -114,20 +162,51 @@ impl TerminalElement {
     }
   }
 
+  /// 绘制选区背景
+  fn paint_selection(
+    window: &mut Window,
+    origin: Point<Pixels>,
+    content: &TerminalContent,
+    char_width: Pixels,
+    char_height: Pixels,
+  ) {
+    let Some(selection) = &content.selection else {
+      return;
+    };
+    for row in 0..content.terminal_bounds.num_lines() {
+      let Some((first, last)) =
+        selection.columns_in_row(row as i32, content.terminal_bounds.num_columns())
+      else {
+        continue;
+      };
+      let bounds = Bounds {
+        origin: Point::new(
+          origin.x + first as f32 * char_width,
+          origin.y + row as f32 * char_height,
+        ),
+        size: Size::new(
+          (last + 1).saturating_sub(first) as f32 * char_width,
+          char_height,
+        ),
+      };
+      window.paint_quad(fill(bounds, gpui::rgba(0x264f78cc)));
+    }
+  }
+
   /// 绘制单元格背景
   fn paint_cell_background(
     window: &mut Window,
     origin: Point<Pixels>,
     row: usize,
     col: usize,
-    bg: [u8; 3],
+    background: CellBackground,
     char_width: Pixels,
     char_height: Pixels,
   ) {
-    if bg == [30, 30, 30] {
+    if background.is_default() {
       return;
     }
-    let bg_color = rgb_to_hsla(bg);
+    let bg_color = rgb_to_hsla(background.color);
     let bg_bounds = Bounds {
       origin: Point::new(
         origin.x + col as f32 * char_width,
@@ -138,6 +217,82 @@ impl TerminalElement {
     window.paint_quad(fill(bg_bounds, bg_color));
   }
 
+  /// 绘制列参考线，位于单元格背景之上、文本之下；超出网格宽度的列不绘制
+  fn paint_column_guides(
+    window: &mut Window,
+    bounds: Bounds<Pixels>,
+    columns: &[usize],
+    num_columns: usize,
+    char_width: Pixels,
+  ) {
+    for &column in columns {
+      if column == 0 || column >= num_columns {
+        continue;
+      }
+      let guide = Bounds {
+        origin: Point::new(
+          bounds.origin.x + column as f32 * char_width,
+          bounds.origin.y,
+        ),
+        size: Size::new(px(1.), bounds.size.height),
+      };
+      window.paint_quad(fill(guide, gpui::rgba(0xffffff14)));
+    }
+  }
+
+  /// 绘制当前跳转到的高亮匹配行
+  fn paint_active_highlight(
+    window: &mut Window,
+    bounds: Bounds<Pixels>,
+    row: usize,
+    char_height: Pixels,
+  ) {
+    let row_bounds = Bounds {
+      origin: Point::new(bounds.origin.x, bounds.origin.y + row as f32 * char_height),
+      size: Size::new(bounds.size.width, char_height),
+    };
+    window.paint_quad(fill(row_bounds, gpui::rgba(0xffffff1a)));
+  }
+
+  /// 在右侧滚动条区域绘制视口位置与高亮匹配标记
+  fn paint_highlight_marks(window: &mut Window, bounds: Bounds<Pixels>, content: &TerminalContent) {
+    let marks = &content.highlight_marks;
+    let total_lines = content.history_size + content.terminal_bounds.num_lines();
+    if content.history_size == 0 && marks.marks.is_empty() {
+      return;
+    }
+
+    let track_width = px(6.);
+    let track_x = bounds.origin.x + bounds.size.width - track_width;
+    let height = f32::from(bounds.size.height);
+
+    // 视口位置
+    let viewport_top = (content.history_size - content.display_offset) as f32 / total_lines as f32;
+    let viewport_height = content.terminal_bounds.num_lines() as f32 / total_lines as f32;
+    window.paint_quad(fill(
+      Bounds {
+        origin: Point::new(track_x, bounds.origin.y + px(viewport_top * height)),
+        size: Size::new(track_width, px((viewport_height * height).max(4.))),
+      },
+      gpui::rgba(0xffffff14),
+    ));
+
+    // 匹配标记
+    if marks.total_lines == 0 {
+      return;
+    }
+    for mark in marks.marks.iter() {
+      let y = mark.row as f32 / marks.total_lines as f32 * height;
+      window.paint_quad(fill(
+        Bounds {
+          origin: Point::new(track_x, bounds.origin.y + px(y)),
+          size: Size::new(track_width, px(2.)),
+        },
+        rgb_to_hsla(mark.color),
+      ));
+    }
+  }
+
   /// 绘制光标
   fn paint_cursor(
     window: &mut Window,
@@ -145,6 +300,7 @@ impl TerminalElement {
     cursor_row: usize,
     cursor_col: usize,
     cursor_char: char,
+    cursor_color: Hsla,
     font: &Font,
     font_size: Pixels,
     char_width: Pixels,
@@ -160,7 +316,7 @@ impl TerminalElement {
     };
 
     // 绘制光标背景
-    window.paint_quad(fill(cursor_bounds, gpui::rgba(0x80ffffff)));
+    window.paint_quad(fill(cursor_bounds, cursor_color));
 
     // 绘制光标处的字符（反色）
     let cursor_run = Self::create_text_run(
@@ -182,12 +338,13 @@ impl TerminalElement {
   }
 
   /// 布局网格 - 将单元格批处理（类似 Zed 的 layout_grid）
-  fn layout_grid(content: &TerminalContent) -> Vec<BatchedTextRun> {
+  pub fn layout_grid(content: &TerminalContent) -> Vec<BatchedTextRun> {
     let mut batched_runs: Vec<BatchedTextRun> = Vec::new();
     let mut current_batch: Option<BatchedTextRun> = None;
 
     let mut last_row: usize = 0;
     let mut last_col: usize = 0;
+    let default_bg = content.background_rgb();
 
     for indexed in &content.cells {
       let row = indexed.point.line.0 as usize;
@@ -199,8 +356,8 @@ impl TerminalElement {
         continue;
       }
 
-      let mut fg = ansi_color_to_rgb(&cell.fg);
-      let mut bg = ansi_color_to_rgb(&cell.bg);
+      let mut fg = content.resolve_color(&cell.fg);
+      let mut bg = content.resolve_color(&cell.bg);
 
       // 处理反色（inverse）标志
       if cell.flags.contains(Flags::INVERSE) {
@@ -211,9 +368,7 @@ impl TerminalElement {
       let c = cell.c;
 
       // 跳过空白字符但保留背景
-      if c == ' '
-        && bg == [30, 30, 30]
-        && !cell.flags.intersects(Flags::UNDERLINE | Flags::STRIKEOUT)
+      if c == ' ' && bg == default_bg && !cell.flags.intersects(Flags::UNDERLINE | Flags::STRIKEOUT)
       {
         if let Some(batch) = current_batch.take() {
           batched_runs.push(batch);
@@ -296,17 +451,43 @@ impl Element for TerminalElement {
   ) -> Self::PrepaintState {
     self.calculate_char_dimensions(window);
 
-    // 从 Terminal 实体获取最新内容
+    // 先处理待处理的内部事件（滚动、粘贴等），再从 Terminal 实体获取最新内容
+    let (char_width, char_height) = (self.char_width, self.char_height);
+    // 按可用空间和字符尺寸计算行列数，变化时调整终端和 PTY 大小
+    let rows = ((bounds.size.height / char_height).floor() as usize).max(1);
+    let view_cols = ((bounds.size.width / char_width).floor() as usize).max(1);
+    let pan_column = self.terminal.update(cx, |terminal, cx| {
+      let cols = terminal.columns_for_view(view_cols);
+      terminal.resize(TerminalBounds::new(
+        char_width,
+        char_height,
+        bounds,
+        rows,
+        cols,
+      ));
+      terminal.sync(cx);
+      let grid = terminal.content().terminal_bounds;
+      terminal.set_layout_bounds(TerminalBounds::new(
+        char_width,
+        char_height,
+        bounds,
+        grid.num_lines(),
+        grid.num_columns(),
+      ));
+      terminal.pan_column()
+    });
     let content = self.terminal.read(cx).content().clone();
     self.content = content.clone();
+    let background_color = rgb_to_hsla(content.background_rgb());
 
     LayoutState {
       bounds,
       content,
       char_width: self.char_width,
       char_height: self.char_height,
-      background_color: gpui::rgb(0x1e1e1e).into(),
+      background_color,
       cursor_visible: true,
+      pan_column,
     }
   }
 
@@ -320,32 +501,77 @@ impl Element for TerminalElement {
     window: &mut Window,
     cx: &mut App,
   ) {
-    let origin = layout.bounds.origin;
+    // 网格比视图宽时按平移位置左移绘制，超出视图的部分裁掉
+    let mask = ContentMask {
+      bounds: layout.bounds,
+    };
+    window.with_content_mask(Some(mask), |window| self.paint_grid(layout, window, cx));
+  }
+}
+
+impl TerminalElement {
+  /// 绘制背景、单元格、选区、文本和光标
+  fn paint_grid(&mut self, layout: &LayoutState, window: &mut Window, cx: &mut App) {
+    let origin = Point::new(
+      layout.bounds.origin.x - layout.pan_column as f32 * layout.char_width,
+      layout.bounds.origin.y,
+    );
     let content = &layout.content;
     let char_width = layout.char_width;
     let char_height = layout.char_height;
 
+    Metric::FramesPainted.record(1);
+
     // 绘制背景
     window.paint_quad(fill(layout.bounds, layout.background_color));
+    if let Some(tint) = self.background_tint {
+      window.paint_quad(fill(layout.bounds, rgb_to_hsla(tint).opacity(0.35)));
+    }
 
     // 准备字体
-    let font_size = px(14.);
+    let font_size = self.font_size;
     let font = Self::create_font();
 
     // 先绘制所有单元格背景
+    let default_bg = content.background_rgb();
     for indexed in &content.cells {
       let row = indexed.point.line.0 as usize;
       let col = indexed.point.column.0 as usize;
       let cell = &indexed.cell;
 
-      let mut bg = ansi_color_to_rgb(&cell.bg);
+      let mut bg = content.resolve_color(&cell.bg);
 
       // 处理反色（inverse）标志
       if cell.flags.contains(Flags::INVERSE) {
-        bg = ansi_color_to_rgb(&cell.fg);
+        bg = content.resolve_color(&cell.fg);
       }
 
-      Self::paint_cell_background(window, origin, row, col, bg, char_width, char_height);
+      Self::paint_cell_background(
+        window,
+        origin,
+        row,
+        col,
+        CellBackground {
+          color: bg,
+          default: default_bg,
+        },
+        char_width,
+        char_height,
+      );
+    }
+
+    Self::paint_column_guides(
+      window,
+      Bounds::new(origin, layout.bounds.size),
+      &self.column_guides,
+      content.terminal_bounds.num_columns(),
+      char_width,
+    );
+
+    Self::paint_selection(window, origin, content, char_width, char_height);
+
+    if let Some(row) = content.active_highlight_row {
+      Self::paint_active_highlight(window, layout.bounds, row, char_height);
     }
 
     // 批处理绘制文本
@@ -380,8 +606,9 @@ impl Element for TerminalElement {
     let cursor_row = cursor.point.line.0 as usize;
     let cursor_col = cursor.point.column.0 as usize;
 
-    // 检查光标是否可见（根据光标形状）
+    // 检查光标是否可见（根据光标形状，以及滚动到历史中时光标是否仍在视口内）
     let cursor_visible = layout.cursor_visible
+      && cursor_row < content.terminal_bounds.num_lines()
       && !matches!(
         cursor.shape,
         alacritty_terminal::vte::ansi::CursorShape::Hidden
@@ -394,6 +621,10 @@ impl Element for TerminalElement {
         cursor_row,
         cursor_col,
         content.cursor_char,
+        content
+          .cursor_rgb()
+          .map(rgb_to_hsla)
+          .unwrap_or_else(|| gpui::rgba(0x80ffffff).into()),
         &font,
         font_size,
         char_width,
@@ -401,6 +632,8 @@ impl Element for TerminalElement {
         cx,
       );
     }
+
+    Self::paint_highlight_marks(window, layout.bounds, content);
   }
 }
 
@@ -0,0 +1,33 @@
+use alacritty_terminal::event::EventListener;
+use alacritty_terminal::term::Term;
+
+/// 统计用户向上滚动后新到达、还没看到的输出行数
+///
+/// 滚动到历史中时 alacritty 会随新输出增大显示偏移，保持视口内容不动；
+/// 偏移的增量就是视口下方新增的行数。历史已满时偏移不再增加，计数也随之停止。
+#[derive(Default)]
+pub struct UnseenOutput {
+  lines: usize,
+  offset_before: usize,
+}
+
+impl UnseenOutput {
+  /// 解析一块输出前记录显示偏移
+  pub fn begin<T: EventListener>(&mut self, term: &Term<T>) {
+    self.offset_before = term.grid().display_offset();
+  }
+
+  /// 解析一块输出后累计新增的行数；视口在底部时输出直接可见，不计数
+  pub fn finish<T: EventListener>(&mut self, term: &Term<T>) {
+    if self.offset_before > 0 {
+      let offset = term.grid().display_offset();
+      self.lines += offset.saturating_sub(self.offset_before);
+    }
+  }
+
+  /// 视口下方的新行数；用户向下滚动后减少，回到底部时清零
+  pub fn lines(&mut self, display_offset: usize) -> usize {
+    self.lines = self.lines.min(display_offset);
+    self.lines
+  }
+}
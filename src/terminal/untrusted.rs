@@ -0,0 +1,125 @@
+use std::borrow::Cow;
+
+/// 不受信任的输出中允许的 OSC 编号：只影响颜色，不会产生其他副作用
+const ALLOWED_OSC: &[&str] = &["4", "10", "11", "12", "104", "110", "111", "112", "7719"];
+
+/// 判断 OSC 编号时最多缓存的字符数，超过后按不允许处理
+const MAX_OSC_NUMBER: usize = 8;
+
+#[derive(Default)]
+enum State {
+  #[default]
+  Ground,
+  /// 收到 ESC，等待下一个字符
+  Escape,
+  /// 收到 `ESC ]`，正在读取 OSC 编号
+  OscNumber(String),
+  /// 字符串序列（OSC、DCS、APC 等）的内容，`pass` 为是否原样输出
+  Body { pass: bool },
+  /// 字符串序列中收到 ESC，可能是 ST（`ESC \`）
+  BodyEscape { pass: bool },
+}
+
+/// 不受信任输出的过滤器，在解析前去掉会产生副作用的转义序列
+///
+/// 连接到未知或不可信的系统时，输出可以修改标题、写入剪贴板（OSC 52）、生成可点击的链接
+/// （OSC 8）、报告工作目录、发送通知或显示内联图片（DCS sixel、APC kitty 图形、OSC 1337）。
+/// 这里只保留改变颜色的 OSC，其他 OSC 以及 DCS、APC、PM、SOS 整段丢弃；其余输出原样返回。
+/// 未启用时不做任何处理。
+pub struct UntrustedFilter {
+  enabled: bool,
+  state: State,
+}
+
+impl UntrustedFilter {
+  pub fn new(enabled: bool) -> Self {
+    Self {
+      enabled,
+      state: State::Ground,
+    }
+  }
+
+  pub fn reset(&mut self) {
+    self.state = State::Ground;
+  }
+
+  pub fn process<'a>(&mut self, text: &'a str) -> Cow<'a, str> {
+    if !self.enabled || matches!(self.state, State::Ground) && !text.contains('\x1b') {
+      return Cow::Borrowed(text);
+    }
+    let mut output = String::with_capacity(text.len());
+    for c in text.chars() {
+      self.push(c, &mut output);
+    }
+    Cow::Owned(output)
+  }
+
+  fn push(&mut self, c: char, output: &mut String) {
+    match std::mem::take(&mut self.state) {
+      State::Ground => {
+        if c == '\x1b' {
+          self.state = State::Escape;
+        } else {
+          output.push(c);
+        }
+      }
+      State::Escape => match c {
+        ']' => self.state = State::OscNumber(String::new()),
+        // DCS、SOS、PM、APC
+        'P' | 'X' | '^' | '_' => self.state = State::Body { pass: false },
+        '\x1b' => {
+          output.push('\x1b');
+          self.state = State::Escape;
+        }
+        c => {
+          output.push('\x1b');
+          output.push(c);
+        }
+      },
+      State::OscNumber(mut number) => {
+        if c.is_ascii_digit() && number.len() < MAX_OSC_NUMBER {
+          number.push(c);
+          self.state = State::OscNumber(number);
+          return;
+        }
+        let pass = ALLOWED_OSC.contains(&number.as_str());
+        if pass {
+          output.push_str("\x1b]");
+          output.push_str(&number);
+        }
+        self.state = State::Body { pass };
+        self.push(c, output);
+      }
+      State::Body { pass } => match c {
+        '\x07' => {
+          if pass {
+            output.push(c);
+          }
+        }
+        // CAN、SUB 中止序列
+        '\x18' | '\x1a' => {
+          if pass {
+            output.push(c);
+          }
+        }
+        '\x1b' => self.state = State::BodyEscape { pass },
+        c => {
+          if pass {
+            output.push(c);
+          }
+          self.state = State::Body { pass };
+        }
+      },
+      State::BodyEscape { pass } => {
+        if pass {
+          output.push_str("\x1b\\");
+        }
+        // ESC 后不是 `\` 时序列同样结束，ESC 开始新的转义序列
+        if c != '\\' {
+          self.state = State::Escape;
+          self.push(c, output);
+        }
+      }
+    }
+  }
+}
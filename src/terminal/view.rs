@@ -1,40 +1,1277 @@
+use crate::global_search::FindInTerminals;
+use crate::settings::Settings;
+use crate::tab_kind::{self, TabKindDescriptor};
+use crate::terminal::content::{SelectionType, rgb_to_hsla};
+use crate::terminal::encoding::TerminalEncoding;
+use crate::terminal::environment;
+use crate::terminal::host;
+use crate::terminal::keys::{BackspaceKey, DeleteKey};
+use crate::terminal::long_line;
+use crate::terminal::pool;
+use crate::terminal::print::{self, PrintOptions};
+use crate::terminal::stderr;
 use crate::terminal::terminal::Terminal;
-use crate::terminal::terminal_element::TerminalElement;
+use crate::terminal::terminal_element::{DEFAULT_FONT_SIZE, TerminalElement};
+use crate::terminal::{LocalPty, TerminalOptions, TerminalSize};
+use alacritty_terminal::grid::Scroll;
+use alacritty_terminal::term::TermMode;
 use gpui::*;
+use gpui_component::button::{Button, ButtonVariants as _};
+use gpui_component::checkbox::Checkbox;
+use gpui_component::dialog::DialogButtonProps;
+use gpui_component::input::{Escape as InputEscape, Input, InputEvent, InputState};
+use gpui_component::menu::ContextMenuExt as _;
+use gpui_component::notification::Notification;
+use gpui_component::tooltip::Tooltip;
+use gpui_component::{
+  Disableable as _, Icon, IconName, Sizable as _, WindowExt as _, h_flex, v_flex,
+};
+use std::cell::Cell;
+use std::ffi::OsString;
+use std::rc::Rc;
+use std::time::Duration;
+
+/// 终端视图的按键上下文
+pub const KEY_CONTEXT: &str = "Terminal";
+
+actions!(
+  terminal,
+  [
+    /// 滚动到下一个高亮匹配
+    ScrollToNextHighlight,
+    /// 滚动到上一个高亮匹配
+    ScrollToPreviousHighlight,
+    /// 复制选中的文本
+    Copy,
+    /// 粘贴剪贴板内容
+    Paste,
+    /// 在当前终端中执行选中的文本
+    RunSelection,
+    /// 在新的终端 Tab 中执行选中的文本
+    RunSelectionInNewTab,
+    /// 放大当前终端
+    ZoomIn,
+    /// 缩小当前终端
+    ZoomOut,
+    /// 恢复当前终端的默认字号
+    ResetZoom,
+    /// 将终端调整为 80×24
+    ResizeTo80x24,
+    /// 将终端调整为 120×40
+    ResizeTo120x40,
+    /// 将终端调整为自定义的列数和行数
+    ResizeToCustom,
+    /// 进入调整大小模式，用方向键逐格调整列数和行数
+    ResizeWithKeyboard,
+    /// 重置终端（RIS）
+    ResetTerminal,
+    /// 显示或隐藏输入行历史
+    ToggleInputHistory,
+    /// 打开或关闭多行命令编辑框
+    ToggleComposer,
+    /// 打印屏幕或选区
+    Print
+  ]
+);
+
+/// 终端 Tab 类型，状态为 `TerminalOptions`
+pub static TERMINAL_TAB_KIND: TabKindDescriptor = TabKindDescriptor {
+  id: "terminal",
+  icon: IconName::SquareTerminal,
+  menu_label: Some("Terminal"),
+  prompt: None,
+  build: build_terminal_tab_from_state,
+  title: |_, _| "Terminal".into(),
+  detail: terminal_detail,
+  focus_handle: terminal_focus_handle,
+  serialize: serialize_terminal_options,
+};
+
+/// 先选择环境变量集再打开的终端 Tab 类型，状态同 `TERMINAL_TAB_KIND`
+pub static ENVIRONMENT_TERMINAL_TAB_KIND: TabKindDescriptor = TabKindDescriptor {
+  id: "environment_terminal",
+  icon: IconName::SquareTerminal,
+  menu_label: Some("Terminal in Environment…"),
+  prompt: Some(environment::prompt_environment),
+  build: build_terminal_tab_from_state,
+  title: |view, cx| {
+    view
+      .clone()
+      .downcast::<TerminalView>()
+      .ok()
+      .and_then(|view| {
+        view
+          .read(cx)
+          .terminal
+          .read(cx)
+          .options()
+          .environment
+          .clone()
+      })
+      .map_or_else(|| "Terminal".into(), SharedString::from)
+  },
+  detail: terminal_detail,
+  focus_handle: terminal_focus_handle,
+  serialize: serialize_terminal_options,
+};
+
+/// 不保留滚动历史、禁止复制的终端 Tab 类型，用于敏感会话
+pub static PRIVATE_TERMINAL_TAB_KIND: TabKindDescriptor = TabKindDescriptor {
+  id: "private_terminal",
+  icon: IconName::EyeOff,
+  menu_label: Some("Private Terminal"),
+  prompt: None,
+  build: |_, window, cx| {
+    let options = TerminalOptions {
+      no_scrollback: true,
+      ..TerminalOptions::default()
+    };
+    build_terminal_tab(options, window, cx)
+  },
+  title: |_, _| "Private Terminal".into(),
+  detail: |_, _| Some("No scrollback, copy disabled".into()),
+  focus_handle: terminal_focus_handle,
+  serialize: tab_kind::no_state,
+};
+
+/// 不信任输出的终端 Tab 类型，用于连接未知或不可信的系统，状态同 `TERMINAL_TAB_KIND`
+pub static UNTRUSTED_TERMINAL_TAB_KIND: TabKindDescriptor = TabKindDescriptor {
+  id: "untrusted_terminal",
+  icon: IconName::TriangleAlert,
+  menu_label: Some("Untrusted Terminal"),
+  prompt: None,
+  build: |state, window, cx| {
+    let options: TerminalOptions = state
+      .and_then(|state| serde_json::from_value(state.clone()).ok())
+      .unwrap_or_default();
+    let options = TerminalOptions {
+      untrusted: true,
+      ..options
+    };
+    build_terminal_tab(options, window, cx)
+  },
+  title: |_, _| "Untrusted Terminal".into(),
+  detail: |_, _| Some("Links, images, clipboard and title changes from output are disabled".into()),
+  focus_handle: terminal_focus_handle,
+  serialize: serialize_terminal_options,
+};
+
+/// 创建本地 shell 终端
+fn build_terminal_tab(
+  mut options: TerminalOptions,
+  window: &mut Window,
+  cx: &mut App,
+) -> Result<AnyView, String> {
+  let mut env = shell_env(cx);
+  // 与 shell_env 中的 stderr 标记变量一致
+  options.stderr_tint = cx.global::<Settings>().stderr_tint;
+  let mut pooled = None;
+  if let Some(name) = &options.environment {
+    // 环境已从设置中删除时照常打开，只是没有这些变量
+    match cx.global::<Settings>().environment(name) {
+      Some(set) => {
+        env.extend(
+          set
+            .vars
+            .iter()
+            .map(|(key, value)| (key.clone(), value.into())),
+        );
+        options.untrusted |= set.untrusted;
+      }
+      None => eprintln!("Environment \"{}\" not found in settings", name),
+    }
+  } else {
+    pooled = pool::take(cx);
+  }
+  let pty = match pooled {
+    Some(pty) => pty,
+    None => {
+      let shell = cx.global::<Settings>().shell.clone();
+      LocalPty::new(TerminalSize::default_size(), shell.as_deref(), &env)
+        .map_err(|e| format!("Failed to create PTY: {}", e))?
+    }
+  };
+  let terminal =
+    cx.new(|cx| Terminal::new(Box::new(pty), options, cx).expect("Failed to create terminal"));
+  Ok(cx.new(|cx| TerminalView::new(terminal, window, cx)).into())
+}
+
+/// 所有本地终端的 shell 都附加的环境变量
+pub(crate) fn shell_env(cx: &App) -> Vec<(String, OsString)> {
+  #[cfg(unix)]
+  let mut env = crate::askpass::Askpass::terminal_env(cx);
+  #[cfg(not(unix))]
+  let mut env = Vec::new();
+  env.extend(stderr::terminal_env(cx.global::<Settings>().stderr_tint));
+  env
+}
+
+fn build_terminal_tab_from_state(
+  state: Option<&serde_json::Value>,
+  window: &mut Window,
+  cx: &mut App,
+) -> Result<AnyView, String> {
+  let options = state
+    .and_then(|state| serde_json::from_value(state.clone()).ok())
+    .unwrap_or_default();
+  build_terminal_tab(options, window, cx)
+}
+
+fn serialize_terminal_options(view: &AnyView, cx: &App) -> serde_json::Value {
+  view
+    .clone()
+    .downcast::<TerminalView>()
+    .ok()
+    .and_then(|view| serde_json::to_value(view.read(cx).terminal.read(cx).options()).ok())
+    .unwrap_or_default()
+}
+
+/// 注入了环境变量集的终端在悬停提示中显示环境名称
+fn terminal_detail(view: &AnyView, cx: &App) -> Option<SharedString> {
+  let view = view.clone().downcast::<TerminalView>().ok()?;
+  let name = view
+    .read(cx)
+    .terminal
+    .read(cx)
+    .options()
+    .environment
+    .clone()?;
+  Some(format!("Environment: {}", name).into())
+}
+
+fn terminal_focus_handle(view: &AnyView, cx: &App) -> Option<FocusHandle> {
+  view
+    .clone()
+    .downcast::<TerminalView>()
+    .ok()
+    .map(|view| view.focus_handle(cx))
+}
+
+/// 单个终端视图可缩放的字号范围
+const MIN_FONT_SIZE: f32 = 6.;
+const MAX_FONT_SIZE: f32 = 72.;
+
+/// 调整大小后显示行列数的时长
+const GRID_OVERLAY_DURATION: Duration = Duration::from_secs(1);
+/// 滚轮每一格转换成的方向键次数上限，避免设置值过大时向 PTY 写入大量数据
+const MAX_ALTERNATE_SCROLL_LINES: usize = 20;
 
 /// Terminal view component using GPUI
 pub struct TerminalView {
   terminal: Entity<Terminal>,
   focus_handle: FocusHandle,
+  /// 尚未凑满一行的滚动量（触控板的像素级滚动）
+  scroll_remainder: f32,
+  /// 尚未凑满一列的横向平移量
+  pan_remainder: f32,
+  /// 本视图的字号，独立于其他终端缩放
+  font_size: Pixels,
+  /// 最近一次观察到的列数和行数
+  grid_size: Option<(usize, usize)>,
+  /// 调整大小时临时显示的“列×行”
+  grid_overlay: Option<Task<()>>,
+  /// 是否处于调整大小模式（方向键逐格调整列数和行数）
+  resize_mode: bool,
+  /// 用户已关闭的网格溢出提示
+  dismissed_overflow: Option<(usize, usize)>,
+  /// 用户已关闭二进制输出提示，输出恢复正常前不再显示
+  dismissed_flood: bool,
+  /// 是否显示输入行历史
+  input_history_open: bool,
+  /// 多行命令编辑框，关闭时为 None
+  composer: Option<(Entity<InputState>, Subscription)>,
+  _subscriptions: Vec<Subscription>,
 }
 
 impl TerminalView {
   /// 创建新的 TerminalView，使用已存在的 Terminal Entity
-  pub fn new(terminal: Entity<Terminal>, cx: &mut Context<Self>) -> Self {
+  pub fn new(terminal: Entity<Terminal>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+    let focus_handle = cx.focus_handle();
+
+    // 焦点变化（包括窗口激活状态变化）转发给 Terminal，用于焦点报告
+    let subscriptions = vec![
+      cx.on_focus_in(&focus_handle, window, |this, _window, cx| {
+        this.focus_changed(true, cx);
+      }),
+      cx.on_focus_out(&focus_handle, window, |this, _event, _window, cx| {
+        this.resize_mode = false;
+        this.focus_changed(false, cx);
+      }),
+      cx.observe_window_activation(window, |this, window, cx| {
+        if this.focus_handle.is_focused(window) {
+          this.focus_changed(window.is_window_active(), cx);
+        }
+      }),
+      cx.observe(&terminal, |this, terminal, cx| {
+        if !terminal.read(cx).content().binary_flood {
+          this.dismissed_flood = false;
+        }
+        let grid_size = terminal.read(cx).grid_size();
+        if grid_size != this.grid_size {
+          // 首次适配视图大小时不显示
+          if this.grid_size.is_some() {
+            this.show_grid_overlay(cx);
+          }
+          this.grid_size = grid_size;
+        }
+      }),
+    ];
+
     Self {
       terminal,
-      focus_handle: cx.focus_handle(),
+      focus_handle,
+      scroll_remainder: 0.,
+      pan_remainder: 0.,
+      font_size: DEFAULT_FONT_SIZE,
+      grid_size: None,
+      grid_overlay: None,
+      resize_mode: false,
+      dismissed_overflow: None,
+      dismissed_flood: false,
+      input_history_open: false,
+      composer: None,
+      _subscriptions: subscriptions,
     }
   }
 
-  /// 处理按键事件
-  fn handle_key_down(
+  fn focus_changed(&mut self, focused: bool, cx: &mut Context<Self>) {
+    self.terminal.update(cx, |terminal, _cx| {
+      terminal.focus_changed(focused);
+    });
+  }
+
+  /// 全屏应用在网格之外绘制时的提示，提供重新发送窗口大小的按钮
+  fn render_overflow_hint(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+    let overflow = self.terminal.read(cx).content().grid_overflow?;
+    if self.dismissed_overflow == Some(overflow) {
+      return None;
+    }
+    let (cols, rows) = self.grid_size?;
+    let (max_row, max_col) = overflow;
+
+    Some(
+      h_flex()
+        .gap_2()
+        .px_2()
+        .py_1()
+        .rounded_md()
+        .bg(rgba(0x3c3c3cee))
+        .text_xs()
+        .text_color(rgb(0xe5c07b))
+        .cursor_default()
+        .child(format!(
+          "The application is drawing outside the {}×{} grid (up to {}×{})",
+          cols,
+          rows,
+          max_col.max(cols),
+          max_row.max(rows)
+        ))
+        .child(
+          Button::new("overflow-redraw")
+            .xsmall()
+            .primary()
+            .label("Redraw")
+            .on_click(cx.listener(|this, _, _window, cx| {
+              this.terminal.update(cx, |terminal, _cx| terminal.redraw());
+            })),
+        )
+        .child(
+          Button::new("overflow-dismiss")
+            .xsmall()
+            .ghost()
+            .label("Dismiss")
+            .on_click(cx.listener(move |this, _, _window, cx| {
+              this.dismissed_overflow = Some(overflow);
+              cx.notify();
+            })),
+        ),
+    )
+  }
+
+  /// 二进制输出提示，提供中断并重置终端的按钮
+  fn render_flood_banner(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+    if !self.terminal.read(cx).content().binary_flood || self.dismissed_flood {
+      return None;
+    }
+
+    Some(
+      h_flex()
+        .gap_2()
+        .px_2()
+        .py_1()
+        .rounded_md()
+        .bg(rgba(0x3c3c3cee))
+        .text_xs()
+        .text_color(rgb(0xf14c4c))
+        .cursor_default()
+        .child("This looks like binary output")
+        .child(
+          Button::new("flood-stop")
+            .xsmall()
+            .danger()
+            .label("Stop and Reset")
+            .on_click(cx.listener(|this, _, _window, cx| {
+              this
+                .terminal
+                .update(cx, |terminal, _cx| terminal.stop_binary_flood());
+            })),
+        )
+        .child(
+          Button::new("flood-dismiss")
+            .xsmall()
+            .ghost()
+            .label("Dismiss")
+            .on_click(cx.listener(|this, _, _window, cx| {
+              this.dismissed_flood = true;
+              cx.notify();
+            })),
+        ),
+    )
+  }
+
+  /// 向上滚动后有新输出时显示的提示，点击回到底部
+  fn render_unseen_pill(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+    let lines = self.terminal.read(cx).content().unseen_lines;
+    if lines == 0 {
+      return None;
+    }
+    Some(
+      h_flex()
+        .absolute()
+        .bottom_4()
+        .left_0()
+        .right_0()
+        .justify_center()
+        .child(
+          Button::new("jump-to-bottom")
+            .small()
+            .primary()
+            .rounded_full()
+            .label(if lines == 1 {
+              "1 new line ↓".to_string()
+            } else {
+              format!("{} new lines ↓", lines)
+            })
+            .tooltip("Jump to bottom (End)")
+            .on_click(cx.listener(|this, _, _window, cx| this.scroll_to_bottom(cx))),
+        ),
+    )
+  }
+
+  fn show_grid_overlay(&mut self, cx: &mut Context<Self>) {
+    self.grid_overlay = Some(cx.spawn(async move |this, cx| {
+      cx.background_executor().timer(GRID_OVERLAY_DURATION).await;
+      let _ = this.update(cx, |this, cx| {
+        this.grid_overlay = None;
+        cx.notify();
+      });
+    }));
+    cx.notify();
+  }
+
+  fn render_grid_overlay(&self) -> Option<impl IntoElement> {
+    self.grid_overlay.as_ref()?;
+    // 调整大小模式有自己的提示
+    if self.resize_mode {
+      return None;
+    }
+    let (cols, rows) = self.grid_size?;
+    Some(
+      div()
+        .absolute()
+        .inset_0()
+        .flex()
+        .items_center()
+        .justify_center()
+        .child(
+          div()
+            .px_3()
+            .py_1()
+            .rounded_md()
+            .bg(rgba(0x3c3c3cee))
+            .text_color(rgb(0xd4d4d4))
+            .child(format!("{}×{}", cols, rows)),
+        ),
+    )
+  }
+
+  /// 将终端调整为指定的列数和行数：按单元格尺寸计算差值并调整窗口大小
+  pub fn resize_grid(&mut self, cols: usize, rows: usize, window: &mut Window, cx: &mut App) {
+    let terminal = self.terminal.read(cx);
+    let (Some(layout), Some((current_cols, current_rows))) =
+      (terminal.layout_bounds(), terminal.grid_size())
+    else {
+      return;
+    };
+    let viewport = window.viewport_size();
+    window.resize(size(
+      viewport.width + layout.cell_width * (cols as f32 - current_cols as f32),
+      viewport.height + layout.line_height * (rows as f32 - current_rows as f32),
+    ));
+  }
+
+  fn resize_to_80x24(&mut self, _: &ResizeTo80x24, window: &mut Window, cx: &mut Context<Self>) {
+    self.resize_grid(80, 24, window, cx);
+  }
+
+  fn resize_to_120x40(&mut self, _: &ResizeTo120x40, window: &mut Window, cx: &mut Context<Self>) {
+    self.resize_grid(120, 40, window, cx);
+  }
+
+  fn resize_with_keyboard(
     &mut self,
-    event: &KeyDownEvent,
+    _: &ResizeWithKeyboard,
     _window: &mut Window,
     cx: &mut Context<Self>,
   ) {
-    let data = encode_keystroke(&event.keystroke);
+    self.resize_mode = true;
+    cx.notify();
+  }
+
+  /// 调整大小模式下的按键：方向键逐格增减列数和行数，Enter 或 Esc 结束，其他按键忽略
+  ///
+  /// 每一步都从终端实际的大小开始计算，窗口无法继续放大（最大化或到达屏幕边缘）时不会累积误差
+  fn handle_resize_key(
+    &mut self,
+    keystroke: &Keystroke,
+    window: &mut Window,
+    cx: &mut Context<Self>,
+  ) {
+    if keystroke.key == "enter" || keystroke.key == "escape" {
+      self.resize_mode = false;
+      cx.notify();
+      return;
+    }
+    // 终端还没有完成首次布局时忽略方向键
+    let Some((cols, rows)) = self.terminal.read(cx).grid_size() else {
+      return;
+    };
+    let (cols, rows) = match keystroke.key.as_str() {
+      "left" => (cols.saturating_sub(1).max(1), rows),
+      "right" => (cols + 1, rows),
+      "up" => (cols, rows.saturating_sub(1).max(1)),
+      "down" => (cols, rows + 1),
+      _ => return,
+    };
+    self.resize_grid(cols, rows, window, cx);
+    cx.notify();
+  }
+
+  /// 调整大小模式的提示：终端当前的大小和可用的按键
+  fn render_resize_mode(&self) -> Option<impl IntoElement> {
+    if !self.resize_mode {
+      return None;
+    }
+    let size = self.grid_size.map_or_else(
+      || "…".to_string(),
+      |(cols, rows)| format!("{}×{}", cols, rows),
+    );
+    Some(
+      div()
+        .absolute()
+        .inset_0()
+        .flex()
+        .items_center()
+        .justify_center()
+        .child(
+          v_flex()
+            .items_center()
+            .gap_1()
+            .px_3()
+            .py_2()
+            .rounded_md()
+            .bg(rgba(0x3c3c3cee))
+            .text_color(rgb(0xd4d4d4))
+            .child(size)
+            .child(
+              div()
+                .text_xs()
+                .text_color(rgb(0x9d9d9d))
+                .child("←/→ columns · ↑/↓ rows · Enter or Esc to finish"),
+            ),
+        ),
+    )
+  }
+
+  /// 弹出对话框输入“列x行”
+  fn resize_to_custom(&mut self, _: &ResizeToCustom, window: &mut Window, cx: &mut Context<Self>) {
+    let default_value = self
+      .grid_size
+      .map(|(cols, rows)| format!("{}x{}", cols, rows))
+      .unwrap_or_default();
+    let input = cx.new(|cx| {
+      InputState::new(window, cx)
+        .placeholder("120x40")
+        .default_value(default_value)
+    });
+    let view = cx.entity();
+
+    window.open_dialog(cx, move |dialog, _window, _cx| {
+      let input_for_ok = input.clone();
+      let view = view.clone();
+      dialog
+        .title("Resize Terminal")
+        .w(px(280.))
+        .child(Input::new(&input))
+        .confirm()
+        .on_ok(move |_, window, cx| {
+          let value = input_for_ok.read(cx).value();
+          let Some((cols, rows)) = parse_grid_size(&value) else {
+            window.push_notification(
+              format!("Invalid size \"{}\", expected columns x rows", value),
+              cx,
+            );
+            return false;
+          };
+          view.update(cx, |this, cx| this.resize_grid(cols, rows, window, cx));
+          true
+        })
+    });
+  }
+
+  /// 弹出打印选项，确认后将当前屏幕转换为 HTML 交给系统浏览器打印
+  fn print(&mut self, _: &Print, window: &mut Window, cx: &mut Context<Self>) {
+    let terminal = self.terminal.read(cx);
+    if terminal.options().no_scrollback {
+      window.push_notification(
+        Notification::warning("Printing is disabled in private terminals"),
+        cx,
+      );
+      return;
+    }
+    let has_selection = terminal.has_selection();
+    let options = Rc::new(Cell::new(PrintOptions {
+      selection_only: has_selection,
+      colors: true,
+    }));
+    let terminal = self.terminal.clone();
+
+    window.open_dialog(cx, move |dialog, _window, _cx| {
+      let current = options.get();
+      let options_for_selection = options.clone();
+      let options_for_colors = options.clone();
+      let options_for_ok = options.clone();
+      let terminal = terminal.clone();
+      dialog
+        .title("Print")
+        .w(px(320.))
+        .child(
+          v_flex()
+            .gap_2()
+            .child(
+              Checkbox::new("print-selection-only")
+                .label("Selection only")
+                .checked(current.selection_only)
+                .disabled(!has_selection)
+                .on_click(move |checked, window, _cx| {
+                  let mut options = options_for_selection.get();
+                  options.selection_only = *checked;
+                  options_for_selection.set(options);
+                  window.refresh();
+                }),
+            )
+            .child(
+              Checkbox::new("print-colors")
+                .label("Include colors")
+                .checked(current.colors)
+                .on_click(move |checked, window, _cx| {
+                  let mut options = options_for_colors.get();
+                  options.colors = *checked;
+                  options_for_colors.set(options);
+                  window.refresh();
+                }),
+            ),
+        )
+        .confirm()
+        .on_ok(move |_, window, cx| {
+          let html = print::to_html(terminal.read(cx).content(), options_for_ok.get());
+          match print::write_temp_file(&html) {
+            Ok(path) => cx.open_with_system(&path),
+            Err(e) => window.push_notification(Notification::error(e), cx),
+          }
+          true
+        })
+    });
+  }
+
+  /// 环境变量集标记：顶部横幅和右上角的环境名称
+  fn render_environment_banner(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+    let name = self.terminal.read(cx).options().environment.clone()?;
+    let color = environment::color(&name, cx)?;
+    Some(
+      div()
+        .absolute()
+        .top_0()
+        .left_0()
+        .right_0()
+        .h(px(3.))
+        .bg(rgb_to_hsla(color)),
+    )
+  }
+
+  fn render_environment_indicator(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+    let name = self.terminal.read(cx).options().environment.clone()?;
+    let color = environment::color(&name, cx)?;
+    Some(
+      h_flex()
+        .id("environment-indicator")
+        .px_2()
+        .py_1()
+        .rounded_md()
+        .bg(rgb_to_hsla(color))
+        .text_xs()
+        .text_color(rgb(0xffffff))
+        .cursor_default()
+        .child(name)
+        .tooltip(|window, cx| {
+          Tooltip::new("Environment variables from this set were added to the shell")
+            .build(window, cx)
+        }),
+    )
+  }
+
+  /// 私密终端标记：不保留滚动历史、禁止复制
+  fn render_private_indicator(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+    if !self.terminal.read(cx).options().no_scrollback {
+      return None;
+    }
+    Some(
+      h_flex()
+        .id("private-indicator")
+        .gap_1()
+        .px_2()
+        .py_1()
+        .rounded_md()
+        .bg(rgba(0x3c3c3cee))
+        .text_xs()
+        .text_color(rgb(0xabb2bf))
+        .cursor_default()
+        .child(Icon::new(IconName::EyeOff).xsmall())
+        .child("No scrollback")
+        .tooltip(|window, cx| {
+          Tooltip::new("Private terminal: history is not kept and copy is disabled")
+            .build(window, cx)
+        }),
+    )
+  }
+
+  /// 背景色调：ssh 连接的主机有色调时用主机的，否则用环境变量集的
+  fn background_tint(&self, cx: &App) -> Option<[u8; 3]> {
+    let terminal = self.terminal.read(cx);
+    if let Some(tint) = terminal
+      .remote_host()
+      .and_then(|remote_host| host::tint(&remote_host, cx))
+    {
+      return Some(tint);
+    }
+    let name = terminal.options().environment.as_deref()?;
+    cx.global::<Settings>().environment(name)?.tint
+  }
+
+  /// 设置了色调的 ssh 主机标记
+  fn render_host_indicator(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+    let remote_host = self.terminal.read(cx).remote_host()?;
+    let tint = host::tint(&remote_host, cx)?;
+    Some(
+      h_flex()
+        .id("host-indicator")
+        .gap_1()
+        .px_2()
+        .py_1()
+        .rounded_md()
+        .bg(rgb_to_hsla(tint))
+        .text_xs()
+        .text_color(rgb(0xffffff))
+        .cursor_default()
+        .child(Icon::new(IconName::Globe).xsmall())
+        .child(remote_host)
+        .tooltip(|window, cx| {
+          Tooltip::new("Connected over ssh; the tint comes from \"host_tints\" in settings")
+            .build(window, cx)
+        }),
+    )
+  }
+
+  /// 不受信任输出标记
+  fn render_untrusted_indicator(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+    if !self.terminal.read(cx).options().untrusted {
+      return None;
+    }
+    Some(
+      h_flex()
+        .id("untrusted-indicator")
+        .gap_1()
+        .px_2()
+        .py_1()
+        .rounded_md()
+        .bg(rgba(0x3c3c3cee))
+        .text_xs()
+        .text_color(rgb(0xe5c07b))
+        .cursor_default()
+        .child(Icon::new(IconName::TriangleAlert).xsmall())
+        .child("Untrusted")
+        .tooltip(|window, cx| {
+          Tooltip::new(
+            "Output cannot create links, show images, use the clipboard or change the title",
+          )
+          .build(window, cx)
+        }),
+    )
+  }
+
+  /// 无效字节序列提示：显示替换次数，并提供切换编码的按钮（推测的编码排在最前）
+  fn render_decode_indicator(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+    let status = self.terminal.read(cx).content().decode_status;
+    if status.replaced == 0 {
+      return None;
+    }
+
+    let mut encodings: Vec<_> = TerminalEncoding::ALL
+      .into_iter()
+      .filter(|encoding| *encoding != status.encoding)
+      .collect();
+    if let Some(suggestion) = status.suggestion {
+      encodings.sort_by_key(|encoding| *encoding != suggestion);
+    }
+
+    let terminal = self.terminal.clone();
+    Some(
+      h_flex()
+        .gap_2()
+        .px_2()
+        .py_1()
+        .rounded_md()
+        .bg(rgba(0x3c3c3cee))
+        .text_xs()
+        .text_color(rgb(0xe5c07b))
+        .cursor_default()
+        .child(format!(
+          "{} invalid {} sequence(s) replaced",
+          status.replaced, status.encoding
+        ))
+        .children(encodings.into_iter().map(move |encoding| {
+          let terminal = terminal.clone();
+          let button = Button::new(SharedString::from(format!("encoding-{}", encoding)))
+            .xsmall()
+            .label(format!("Use {}", encoding))
+            .on_click(move |_, _window, cx| {
+              terminal.update(cx, |terminal, _cx| terminal.set_encoding(encoding));
+            });
+          if Some(encoding) == status.suggestion {
+            button.primary()
+          } else {
+            button.ghost()
+          }
+        })),
+    )
+  }
+
+  /// 处理按键事件
+  fn handle_key_down(&mut self, event: &KeyDownEvent, window: &mut Window, cx: &mut Context<Self>) {
+    // 焦点在覆盖层（如多行编辑框）中时按键不发送给终端
+    if !self.focus_handle.is_focused(window) {
+      return;
+    }
+    if self.resize_mode {
+      self.handle_resize_key(&event.keystroke, window, cx);
+      return;
+    }
+    if self.input_history_open && event.keystroke.key == "escape" {
+      self.input_history_open = false;
+      cx.notify();
+      return;
+    }
+    let content = self.terminal.read(cx).content();
+    // 浏览历史时 End 回到底部，不发送给应用
+    if event.keystroke.key == "end"
+      && !event.keystroke.modifiers.modified()
+      && !content.scrolled_to_bottom
+    {
+      self.scroll_to_bottom(cx);
+      return;
+    }
+    let mode = content.mode;
+    let (backspace, delete) = self.erase_keys(cx);
+    let data = encode_keystroke(&event.keystroke, mode, backspace, delete);
     self.terminal.update(cx, |terminal, _cx| {
       let _ = terminal.input(data);
     });
   }
 
+  /// Backspace 和 Delete 键发送的字符：应用通过 DECBKM 设置的模式优先，其次是环境变量集，最后是设置
+  fn erase_keys(&self, cx: &App) -> (BackspaceKey, DeleteKey) {
+    let settings = cx.global::<Settings>();
+    let terminal = self.terminal.read(cx);
+    let set = terminal
+      .options()
+      .environment
+      .as_deref()
+      .and_then(|name| settings.environment(name));
+    let backspace = terminal
+      .backarrow_mode()
+      .or_else(|| set.and_then(|set| set.backspace))
+      .unwrap_or(settings.backspace);
+    let delete = set.and_then(|set| set.delete).unwrap_or(settings.delete);
+    (backspace, delete)
+  }
+
+  /// 处理滚轮事件
+  ///
+  /// 纵向滚动浏览历史；横向滚动（包括 Shift+滚轮）在保留选区而比视图宽的网格上横向平移。
+  /// 备用屏幕没有滚动历史，应用开启 alternate scroll（DECSET 1007）时将滚动转换为方向键
+  fn handle_scroll_wheel(
+    &mut self,
+    event: &ScrollWheelEvent,
+    _window: &mut Window,
+    cx: &mut Context<Self>,
+  ) {
+    let bounds = self.terminal.read(cx).content().terminal_bounds;
+    let line_height = bounds.line_height;
+    let delta = wheel_delta(event, line_height);
+    if !delta.x.is_zero() {
+      self.pan_remainder += f32::from(delta.x) / f32::from(bounds.cell_width);
+      let columns = self.pan_remainder.trunc() as i32;
+      self.pan_remainder -= columns as f32;
+      // 向右滑动（正增量）显示左侧内容，与纵向滚动的方向一致
+      if columns != 0
+        && self
+          .terminal
+          .update(cx, |terminal, _cx| terminal.pan(-columns))
+      {
+        cx.notify();
+      }
+      return;
+    }
+    if delta.y.is_zero() {
+      return;
+    }
+
+    // Ctrl+滚轮只缩放当前终端，行列数和 PTY 大小在下次绘制时重新计算
+    if event.modifiers.control {
+      let steps = f32::from(delta.y) / f32::from(line_height);
+      self.set_font_size(self.font_size + px(steps), cx);
+      return;
+    }
+
+    self.scroll_remainder += f32::from(delta.y) / f32::from(line_height);
+    let lines = self.scroll_remainder.trunc() as i32;
+    if lines == 0 {
+      return;
+    }
+    self.scroll_remainder -= lines as f32;
+
+    let mode = self.terminal.read(cx).content().mode;
+    let lines_per_notch = cx.global::<Settings>().alternate_scroll_lines;
+    if let Some(data) = alternate_scroll_input(lines, &event.delta, lines_per_notch, mode) {
+      self.terminal.update(cx, |terminal, _cx| {
+        let _ = terminal.input(data);
+      });
+      return;
+    }
+
+    self.terminal.update(cx, |terminal, _cx| {
+      terminal.scroll(Scroll::Delta(lines));
+    });
+    cx.notify();
+  }
+
   /// 处理粘贴事件
+  ///
+  /// 应用开启 bracketed paste（DECSET 2004）时由 Terminal 包裹粘贴内容
   fn handle_paste(&mut self, text: &str, cx: &mut Context<Self>) {
     self.terminal.update(cx, |terminal, _cx| {
       terminal.paste(text);
     });
+    cx.notify();
+  }
+
+  fn copy_selection(&mut self, _: &Copy, window: &mut Window, cx: &mut Context<Self>) {
+    if self.terminal.read(cx).options().no_scrollback {
+      window.push_notification(
+        Notification::warning("Copy is disabled in private terminals"),
+        cx,
+      );
+      return;
+    }
+    self.copy(cx);
+  }
+
+  fn paste(&mut self, _: &Paste, _window: &mut Window, cx: &mut Context<Self>) {
+    if let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) {
+      self.handle_paste(&text, cx);
+    }
+  }
+
+  /// 按下左键开始选区：单击按字符，双击按单词，三击按行；按住 Shift 时扩展已有选区
+  fn handle_mouse_down(
+    &mut self,
+    event: &MouseDownEvent,
+    window: &mut Window,
+    cx: &mut Context<Self>,
+  ) {
+    self.focus_handle.focus(window);
+    let Some((point, side)) = self.terminal.read(cx).grid_point(event.position) else {
+      return;
+    };
+    let ty = match event.click_count {
+      1 => SelectionType::Simple,
+      2 => SelectionType::Semantic,
+      _ => SelectionType::Lines,
+    };
+    self.terminal.update(cx, |terminal, _cx| {
+      if event.modifiers.shift && terminal.content().selection.is_some() {
+        terminal.update_selection(point, side);
+      } else {
+        terminal.start_selection(ty, point, side);
+      }
+    });
+    cx.notify();
+  }
+
+  /// 拖动左键扩展选区
+  fn handle_mouse_move(
+    &mut self,
+    event: &MouseMoveEvent,
+    _window: &mut Window,
+    cx: &mut Context<Self>,
+  ) {
+    if event.pressed_button != Some(MouseButton::Left) {
+      return;
+    }
+    let Some((point, side)) = self.terminal.read(cx).grid_point(event.position) else {
+      return;
+    };
+    self.terminal.update(cx, |terminal, _cx| {
+      terminal.update_selection(point, side);
+    });
+    cx.notify();
+  }
+
+  fn run_selection(&mut self, _: &RunSelection, window: &mut Window, cx: &mut Context<Self>) {
+    let terminal = self.terminal.clone();
+    with_selection_text(&self.terminal, window, cx, move |text, window, cx| {
+      confirm_command(text, window, cx, move |text, _window, cx| {
+        run_command(&terminal, &text, cx);
+      });
+    });
+  }
+
+  fn set_font_size(&mut self, font_size: Pixels, cx: &mut Context<Self>) {
+    self.font_size = font_size.clamp(px(MIN_FONT_SIZE), px(MAX_FONT_SIZE));
+    cx.notify();
+  }
+
+  fn zoom_in(&mut self, _: &ZoomIn, _window: &mut Window, cx: &mut Context<Self>) {
+    self.set_font_size(self.font_size + px(1.), cx);
+  }
+
+  fn zoom_out(&mut self, _: &ZoomOut, _window: &mut Window, cx: &mut Context<Self>) {
+    self.set_font_size(self.font_size - px(1.), cx);
+  }
+
+  fn reset_zoom(&mut self, _: &ResetZoom, _window: &mut Window, cx: &mut Context<Self>) {
+    self.set_font_size(DEFAULT_FONT_SIZE, cx);
+  }
+
+  /// 询问是否保留滚动历史后重置终端
+  fn reset_terminal(&mut self, _: &ResetTerminal, window: &mut Window, cx: &mut Context<Self>) {
+    let answer = window.prompt(
+      PromptLevel::Warning,
+      "Reset terminal?",
+      Some("Clears the screen and restores all terminal modes, character sets and colors."),
+      &["Keep Scrollback", "Clear Scrollback", "Cancel"],
+      cx,
+    );
+    let terminal = self.terminal.clone();
+    cx.spawn(async move |_, cx| {
+      let keep_scrollback = match answer.await {
+        Ok(0) => true,
+        Ok(1) => false,
+        _ => return,
+      };
+      let _ = terminal.update(cx, |terminal, _cx| terminal.reset(keep_scrollback));
+    })
+    .detach();
+  }
+
+  fn toggle_input_history(
+    &mut self,
+    _: &ToggleInputHistory,
+    _window: &mut Window,
+    cx: &mut Context<Self>,
+  ) {
+    self.input_history_open = !self.input_history_open;
+    cx.notify();
+  }
+
+  /// 重新发送历史中的一行
+  fn resend_input(&mut self, line: &str, cx: &mut Context<Self>) {
+    self.input_history_open = false;
+    let data = format!("{}\r", line).into_bytes();
+    self.terminal.update(cx, |terminal, _cx| {
+      let _ = terminal.input(data);
+    });
+    cx.notify();
+  }
+
+  /// 终端左下角的输入行历史，点击一行重新发送
+  fn render_input_history(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+    if !self.input_history_open {
+      return None;
+    }
+    let lines = self.terminal.read(cx).input_history();
+    let empty_message = if self.terminal.read(cx).options().no_scrollback {
+      "Input is not recorded in private terminals"
+    } else {
+      "No input lines yet"
+    };
+
+    Some(
+      v_flex()
+        .id("input-history")
+        .absolute()
+        .left_4()
+        .bottom_4()
+        .w(px(360.))
+        .max_h(px(280.))
+        .overflow_y_scroll()
+        .p_1()
+        .rounded_md()
+        .border_1()
+        .border_color(rgb(0x3c3c3c))
+        .bg(rgba(0x252526f5))
+        .text_xs()
+        .text_color(rgb(0xd4d4d4))
+        .cursor_default()
+        .child(
+          h_flex()
+            .px_2()
+            .py_1()
+            .text_color(rgb(0xabb2bf))
+            .child(div().flex_1().child("Input History"))
+            .child(
+              Button::new("input-history-close")
+                .xsmall()
+                .ghost()
+                .icon(IconName::Close)
+                .on_click(cx.listener(|this, _, _window, cx| {
+                  this.input_history_open = false;
+                  cx.notify();
+                })),
+            ),
+        )
+        .children(lines.is_empty().then(|| {
+          div()
+            .px_2()
+            .py_1()
+            .text_color(rgb(0x7f848e))
+            .child(empty_message)
+        }))
+        .children(lines.into_iter().enumerate().map(|(index, line)| {
+          div()
+            .id(("input-history-line", index))
+            .px_2()
+            .py_1()
+            .rounded_sm()
+            .truncate()
+            .cursor_pointer()
+            .hover(|style| style.bg(rgb(0x3c3c3c)))
+            .child(line.clone())
+            .on_click(cx.listener(move |this, _, _window, cx| this.resend_input(&line, cx)))
+        })),
+    )
+  }
+
+  fn toggle_composer(&mut self, _: &ToggleComposer, window: &mut Window, cx: &mut Context<Self>) {
+    if self.composer.is_some() {
+      self.close_composer(window, cx);
+      return;
+    }
+    let input = cx.new(|cx| {
+      InputState::new(window, cx)
+        .auto_grow(3, 12)
+        .placeholder("Compose a multi-line command")
+    });
+    input.update(cx, |input, cx| input.focus(window, cx));
+    let subscription = cx.subscribe_in(&input, window, |this, _, event, window, cx| {
+      if let InputEvent::PressEnter { secondary: true } = event {
+        this.submit_composer(window, cx);
+      }
+    });
+    self.composer = Some((input, subscription));
+    cx.notify();
+  }
+
+  fn close_composer(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+    self.composer = None;
+    self.focus_handle.focus(window);
+    cx.notify();
+  }
+
+  /// 将编辑框中的文本作为一次粘贴发送并回车执行
+  fn submit_composer(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+    let Some((input, _)) = &self.composer else {
+      return;
+    };
+    let text = input.read(cx).value().trim_end().to_string();
+    if text.is_empty() {
+      return;
+    }
+    self.terminal.update(cx, |terminal, cx| {
+      let _ = terminal.submit(&text, cx);
+    });
+    self.close_composer(window, cx);
+  }
+
+  /// 终端底部的多行命令编辑框
+  fn render_composer(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+    let (input, _) = self.composer.as_ref()?;
+    let send_hint = if cfg!(target_os = "macos") {
+      "⌘Enter to send, Esc to cancel"
+    } else {
+      "Ctrl+Enter to send, Esc to cancel"
+    };
+
+    Some(
+      v_flex()
+        .id("composer")
+        .absolute()
+        .left_4()
+        .right_4()
+        .bottom_4()
+        .gap_2()
+        .p_2()
+        .rounded_md()
+        .border_1()
+        .border_color(rgb(0x3c3c3c))
+        .bg(rgba(0x252526f5))
+        .cursor_default()
+        .on_action(cx.listener(|this, _: &InputEscape, window, cx| {
+          this.close_composer(window, cx);
+        }))
+        .child(Input::new(input))
+        .child(
+          h_flex()
+            .gap_2()
+            .text_xs()
+            .text_color(rgb(0x7f848e))
+            .child(div().flex_1().child(send_hint))
+            .child(
+              Button::new("composer-cancel")
+                .xsmall()
+                .ghost()
+                .label("Cancel")
+                .on_click(cx.listener(|this, _, window, cx| this.close_composer(window, cx))),
+            )
+            .child(
+              Button::new("composer-send")
+                .xsmall()
+                .primary()
+                .label("Send")
+                .on_click(cx.listener(|this, _, window, cx| this.submit_composer(window, cx))),
+            ),
+        ),
+    )
   }
 
   /// 获取关联的 Terminal Entity
@@ -84,6 +1321,30 @@ impl TerminalView {
     });
   }
 
+  fn scroll_to_next_highlight(
+    &mut self,
+    _: &ScrollToNextHighlight,
+    _window: &mut Window,
+    cx: &mut Context<Self>,
+  ) {
+    self.terminal.update(cx, |terminal, _cx| {
+      terminal.scroll_to_next_highlight();
+    });
+    cx.notify();
+  }
+
+  fn scroll_to_previous_highlight(
+    &mut self,
+    _: &ScrollToPreviousHighlight,
+    _window: &mut Window,
+    cx: &mut Context<Self>,
+  ) {
+    self.terminal.update(cx, |terminal, _cx| {
+      terminal.scroll_to_previous_highlight();
+    });
+    cx.notify();
+  }
+
   /// 清除屏幕
   pub fn clear(&mut self, cx: &mut Context<Self>) {
     self.terminal.update(cx, |terminal, _cx| {
@@ -91,11 +1352,19 @@ impl TerminalView {
     });
   }
 
-  /// 复制选区
+  /// 复制选区：选中的文本在后台提取，完成后写入剪贴板；私密终端不允许复制
   pub fn copy(&mut self, cx: &mut Context<Self>) {
-    self.terminal.update(cx, |terminal, _cx| {
-      terminal.copy();
-    });
+    let terminal = self.terminal.read(cx);
+    if terminal.options().no_scrollback || !terminal.has_selection() {
+      return;
+    }
+    let text = terminal.selection_text();
+    cx.spawn(async move |_, cx| {
+      if let Some(text) = text.await {
+        let _ = cx.update(|cx| cx.write_to_clipboard(ClipboardItem::new_string(text)));
+      }
+    })
+    .detach();
   }
 }
 
@@ -103,17 +1372,88 @@ impl Render for TerminalView {
   fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
     div()
       .id("terminal-view")
+      .key_context(KEY_CONTEXT)
       .size_full()
       .bg(gpui::rgb(0x1e1e1e))
+      .relative()
       .cursor_text()
-      .child(TerminalElement::new(
-        self.terminal.clone(),
-        self.focus_handle.clone(),
-      ))
+      .child(
+        TerminalElement::new(self.terminal.clone(), self.focus_handle.clone())
+          .font_size(self.font_size)
+          .column_guides(cx.global::<Settings>().column_guides.clone())
+          .background_tint(self.background_tint(cx)),
+      )
+      .child(
+        v_flex()
+          .absolute()
+          .top_2()
+          .right_4()
+          .gap_2()
+          .items_end()
+          .children(self.render_environment_indicator(cx))
+          .children(self.render_host_indicator(cx))
+          .children(self.render_private_indicator(cx))
+          .children(self.render_untrusted_indicator(cx))
+          .children(self.render_flood_banner(cx))
+          .children(self.render_decode_indicator(cx))
+          .children(self.render_overflow_hint(cx)),
+      )
+      .children(self.render_environment_banner(cx))
+      .children(self.render_unseen_pill(cx))
+      .children(self.render_input_history(cx))
+      .children(self.render_composer(cx))
+      .children(self.render_grid_overlay())
+      .children(self.render_resize_mode())
       .on_key_down(cx.listener(|this, event, window, cx| {
         this.handle_key_down(event, window, cx);
       }))
+      .on_scroll_wheel(cx.listener(|this, event, window, cx| {
+        this.handle_scroll_wheel(event, window, cx);
+      }))
+      .on_action(cx.listener(Self::scroll_to_next_highlight))
+      .on_action(cx.listener(Self::scroll_to_previous_highlight))
+      .on_action(cx.listener(Self::copy_selection))
+      .on_action(cx.listener(Self::paste))
+      .on_action(cx.listener(Self::run_selection))
+      .on_action(cx.listener(Self::zoom_in))
+      .on_action(cx.listener(Self::zoom_out))
+      .on_action(cx.listener(Self::reset_zoom))
+      .on_action(cx.listener(Self::resize_to_80x24))
+      .on_action(cx.listener(Self::resize_to_120x40))
+      .on_action(cx.listener(Self::resize_with_keyboard))
+      .on_action(cx.listener(Self::resize_to_custom))
+      .on_action(cx.listener(Self::reset_terminal))
+      .on_action(cx.listener(Self::toggle_input_history))
+      .on_action(cx.listener(Self::toggle_composer))
+      .on_action(cx.listener(Self::print))
+      .on_mouse_down(MouseButton::Left, cx.listener(Self::handle_mouse_down))
+      .on_mouse_move(cx.listener(Self::handle_mouse_move))
       .track_focus(&self.focus_handle)
+      .context_menu({
+        let focus_handle = self.focus_handle.clone();
+        move |menu, _window, _cx| {
+          menu
+            .action_context(focus_handle.clone())
+            .menu("Copy", Box::new(Copy))
+            .menu("Paste", Box::new(Paste))
+            .menu("Run Selection", Box::new(RunSelection))
+            .menu("Find in All Terminals…", Box::new(FindInTerminals))
+            .menu("Input History", Box::new(ToggleInputHistory))
+            .menu("Compose Command…", Box::new(ToggleComposer))
+            .menu("Print…", Box::new(Print))
+            .separator()
+            .menu("Resize to 80×24", Box::new(ResizeTo80x24))
+            .menu("Resize to 120×40", Box::new(ResizeTo120x40))
+            .menu("Custom Size…", Box::new(ResizeToCustom))
+            .menu("Resize with Arrow Keys", Box::new(ResizeWithKeyboard))
+            .separator()
+            .menu("Zoom In", Box::new(ZoomIn))
+            .menu("Zoom Out", Box::new(ZoomOut))
+            .menu("Reset Zoom", Box::new(ResetZoom))
+            .separator()
+            .menu("Reset Terminal…", Box::new(ResetTerminal))
+        }
+      })
   }
 }
 
@@ -123,8 +1463,206 @@ impl Focusable for TerminalView {
   }
 }
 
+/// 在后台提取终端选中的文本，完成后在窗口中处理；没有选区时不调用
+pub fn with_selection_text(
+  terminal: &Entity<Terminal>,
+  window: &mut Window,
+  cx: &mut App,
+  f: impl FnOnce(String, &mut Window, &mut App) + 'static,
+) {
+  let terminal = terminal.read(cx);
+  if !terminal.has_selection() {
+    return;
+  }
+  let text = terminal.selection_text();
+  window
+    .spawn(cx, async move |cx| {
+      if let Some(text) = text.await {
+        let _ = cx.update(|window, cx| f(text, window, cx));
+      }
+    })
+    .detach();
+}
+
+/// 执行命令前确认：多行文本先弹窗预览，单行直接执行
+///
+/// 预览中的超长行和过多的行会被截断，可以在只渲染可见行的对话框中查看全部内容
+pub fn confirm_command(
+  text: String,
+  window: &mut Window,
+  cx: &mut App,
+  run: impl FnOnce(String, &mut Window, &mut App) + 'static,
+) {
+  let lines = text.trim_end().lines().count();
+  if lines <= 1 {
+    run(text, window, cx);
+    return;
+  }
+  prompt_command(text, lines, window, cx, run);
+}
+
+fn prompt_command(
+  text: String,
+  lines: usize,
+  window: &mut Window,
+  cx: &mut App,
+  run: impl FnOnce(String, &mut Window, &mut App) + 'static,
+) {
+  let (preview, hidden) = long_line::soft_cap(
+    text.trim_end(),
+    long_line::PREVIEW_LINE_CAP,
+    long_line::PREVIEW_MAX_LINES,
+  );
+  let answers: &[&str] = if hidden > 0 {
+    &["Run", "Show All", "Cancel"]
+  } else {
+    &["Run", "Cancel"]
+  };
+
+  let answer = window.prompt(
+    PromptLevel::Warning,
+    &format!("Run {} lines as commands?", lines),
+    Some(&preview),
+    answers,
+    cx,
+  );
+  window
+    .spawn(cx, async move |cx| match answer.await {
+      Ok(0) => {
+        let _ = cx.update(|window, cx| run(text, window, cx));
+      }
+      Ok(1) if hidden > 0 => {
+        let _ = cx.update(|window, cx| show_full_command(text, lines, window, cx, run));
+      }
+      _ => {}
+    })
+    .detach();
+}
+
+/// 在对话框中预览全部命令，超长行折成多行，只渲染滚动到的行
+fn show_full_command(
+  text: String,
+  lines: usize,
+  window: &mut Window,
+  cx: &mut App,
+  run: impl FnOnce(String, &mut Window, &mut App) + 'static,
+) {
+  let rows: Rc<Vec<SharedString>> = Rc::new(
+    long_line::wrap_rows(text.trim_end(), long_line::PREVIEW_LINE_CAP)
+      .into_iter()
+      .map(SharedString::from)
+      .collect(),
+  );
+  // 确认只执行一次
+  let pending = Rc::new(Cell::new(Some((text, run))));
+  window.open_dialog(cx, move |dialog, _window, _cx| {
+    let rows = rows.clone();
+    let pending = pending.clone();
+    dialog
+      .title(format!("Run {} lines as commands?", lines))
+      .w(px(720.))
+      .child(
+        uniform_list("command-preview", rows.len(), move |range, _window, _cx| {
+          range
+            .map(|index| div().whitespace_nowrap().child(rows[index].clone()))
+            .collect()
+        })
+        .h(px(360.))
+        .font_family("monospace")
+        .text_xs(),
+      )
+      .confirm()
+      .button_props(DialogButtonProps::default().ok_text("Run"))
+      .on_ok(move |_, window, cx| {
+        if let Some((text, run)) = pending.take() {
+          run(text, window, cx);
+        }
+        true
+      })
+  });
+}
+
+/// 在终端中执行命令，供只持有终端实体的调用方（如确认对话框）使用
+pub fn run_command(terminal: &Entity<Terminal>, text: &str, cx: &mut App) {
+  terminal.update(cx, |terminal, _cx| {
+    let _ = terminal.run_command(text);
+  });
+}
+
+/// 将滚轮事件转换为像素增量
+///
+/// 按住 Shift 时纵向滚动映射为横向滚动，与 Tab 栏的横向滚动行为保持一致
+fn wheel_delta(event: &ScrollWheelEvent, line_height: Pixels) -> Point<Pixels> {
+  let delta = event.delta.pixel_delta(line_height);
+  if event.modifiers.shift && delta.x.is_zero() {
+    point(delta.y, Pixels::ZERO)
+  } else {
+    delta
+  }
+}
+
+/// 解析“列x行”，如 `120x40` 或 `120×40`
+fn parse_grid_size(text: &str) -> Option<(usize, usize)> {
+  let (cols, rows) = text.trim().split_once(['x', 'X', '×'])?;
+  let cols = cols.trim().parse().ok().filter(|cols| *cols > 0)?;
+  let rows = rows.trim().parse().ok().filter(|rows| *rows > 0)?;
+  Some((cols, rows))
+}
+
+/// 编码光标键（方向键、Home、End）
+///
+/// 应用开启 DECCKM（application cursor keys）时使用 SS3 前缀，否则使用 CSI
+fn cursor_key(key: u8, mode: TermMode) -> Vec<u8> {
+  if mode.contains(TermMode::APP_CURSOR) {
+    vec![0x1b, b'O', key]
+  } else {
+    vec![0x1b, b'[', key]
+  }
+}
+
+/// 备用屏幕开启 alternate scroll 时把滚动的行数转换为方向键，其他情况返回 None
+///
+/// 鼠标滚轮每一格发送 `lines_per_notch` 次方向键；触控板的像素增量已按行累计，每行一次
+fn alternate_scroll_input(
+  lines: i32,
+  delta: &ScrollDelta,
+  lines_per_notch: usize,
+  mode: TermMode,
+) -> Option<Vec<u8>> {
+  if !mode.contains(TermMode::ALT_SCREEN | TermMode::ALTERNATE_SCROLL) {
+    return None;
+  }
+  let repeats = match delta {
+    ScrollDelta::Lines(_) => lines_per_notch.clamp(1, MAX_ALTERNATE_SCROLL_LINES),
+    ScrollDelta::Pixels(_) => 1,
+  };
+  let key = if lines > 0 { b'A' } else { b'B' };
+  Some(cursor_key(key, mode).repeat(lines.unsigned_abs() as usize * repeats))
+}
+
+/// 小键盘运算符键在 application keypad 模式下的 SS3 终止字节
+///
+/// 只有 Linux 上 GPUI 用单独的名称上报这些键；小键盘的数字和 Enter 与主键盘同名，无法区分
+fn keypad_key(key: &str) -> Option<u8> {
+  Some(match key {
+    "multiply" => b'j',
+    "add" => b'k',
+    "separator" => b'l',
+    "subtract" => b'm',
+    "decimal" => b'n',
+    "divide" => b'o',
+    "equal" => b'X',
+    _ => return None,
+  })
+}
+
 /// 将 GPUI Keystroke 编码为字节序列
-fn encode_keystroke(keystroke: &Keystroke) -> Vec<u8> {
+fn encode_keystroke(
+  keystroke: &Keystroke,
+  mode: TermMode,
+  backspace: BackspaceKey,
+  delete: DeleteKey,
+) -> Vec<u8> {
   let key = keystroke.key.as_str();
   let modifiers = &keystroke.modifiers;
 
@@ -136,20 +1674,29 @@ fn encode_keystroke(keystroke: &Keystroke) -> Vec<u8> {
     }
   }
 
+  // 小键盘的运算符键：应用开启 DECKPAM（application keypad）时发送 SS3 序列，否则发送字符本身
+  if mode.contains(TermMode::APP_KEYPAD)
+    && let Some(final_byte) = keypad_key(key)
+  {
+    return vec![0x1b, b'O', final_byte];
+  }
+
   // 功能键和特殊键使用 key 字段
   match key {
     "enter" | "return" => return vec![b'\r'],
     "escape" | "esc" => return vec![0x1b],
     "tab" => return vec![b'\t'],
-    "backspace" => return vec![0x7f],
-    "delete" | "del" => return vec![0x1b, b'[', b'3', b'~'],
+    "backspace" => return backspace.bytes().to_vec(),
+    "delete" | "del" => return delete.bytes().to_vec(),
     "insert" | "ins" => return vec![0x1b, b'[', b'2', b'~'],
-    "up" => return vec![0x1b, b'[', b'A'],
-    "down" => return vec![0x1b, b'[', b'B'],
-    "right" => return vec![0x1b, b'[', b'C'],
-    "left" => return vec![0x1b, b'[', b'D'],
-    "home" => return vec![0x1b, b'[', b'H'],
-    "end" => return vec![0x1b, b'[', b'F'],
+    "up" => return cursor_key(b'A', mode),
+    "down" => return cursor_key(b'B', mode),
+    "right" => return cursor_key(b'C', mode),
+    "left" => return cursor_key(b'D', mode),
+    "home" => return cursor_key(b'H', mode),
+    "end" => return cursor_key(b'F', mode),
+    // 关闭 NumLock 时小键盘中间的 5
+    "begin" => return cursor_key(b'E', mode),
     "pageup" | "page up" => return vec![0x1b, b'[', b'5', b'~'],
     "pagedown" | "page down" => return vec![0x1b, b'[', b'6', b'~'],
     "f1" => return vec![0x1b, b'O', b'P'],
@@ -164,6 +1711,18 @@ fn encode_keystroke(keystroke: &Keystroke) -> Vec<u8> {
     "f10" => return vec![0x1b, b'[', b'2', b'1', b'~'],
     "f11" => return vec![0x1b, b'[', b'2', b'3', b'~'],
     "f12" => return vec![0x1b, b'[', b'2', b'4', b'~'],
+    "f13" => return vec![0x1b, b'[', b'2', b'5', b'~'],
+    "f14" => return vec![0x1b, b'[', b'2', b'6', b'~'],
+    "f15" => return vec![0x1b, b'[', b'2', b'8', b'~'],
+    "f16" => return vec![0x1b, b'[', b'2', b'9', b'~'],
+    "f17" => return vec![0x1b, b'[', b'3', b'1', b'~'],
+    "f18" => return vec![0x1b, b'[', b'3', b'2', b'~'],
+    "f19" => return vec![0x1b, b'[', b'3', b'3', b'~'],
+    "f20" => return vec![0x1b, b'[', b'3', b'4', b'~'],
+    // 与 xterm 相同，Menu 键作为 VT220 的 Do 键
+    "menu" => return vec![0x1b, b'[', b'2', b'9', b'~'],
+    // PrintScreen、Pause、ScrollLock 在传统编码中没有对应的序列（xterm 同样不发送），不发送给应用
+    "print" | "pause" | "scroll_lock" => return vec![],
     "space" => return vec![b' '],
     _ => {}
   }
@@ -182,3 +1741,116 @@ fn encode_keystroke(keystroke: &Keystroke) -> Vec<u8> {
 
   vec![]
 }
+
+#[cfg(test)]
+mod tests {
+  use alacritty_terminal::term::TermMode;
+  use gpui::{Keystroke, Modifiers, ScrollDelta, point, px};
+
+  use super::{MAX_ALTERNATE_SCROLL_LINES, alternate_scroll_input, cursor_key, encode_keystroke};
+  use crate::terminal::keys::{BackspaceKey, DeleteKey};
+
+  fn keystroke(key: &str) -> Keystroke {
+    Keystroke {
+      modifiers: Modifiers::default(),
+      key: key.to_string(),
+      key_char: None,
+    }
+  }
+
+  fn encode(key: &str, mode: TermMode) -> Vec<u8> {
+    encode_keystroke(
+      &keystroke(key),
+      mode,
+      BackspaceKey::default(),
+      DeleteKey::default(),
+    )
+  }
+
+  #[test]
+  fn cursor_keys_use_csi_without_decckm() {
+    assert_eq!(cursor_key(b'A', TermMode::empty()), b"\x1b[A");
+    assert_eq!(encode("up", TermMode::empty()), b"\x1b[A");
+    assert_eq!(encode("down", TermMode::empty()), b"\x1b[B");
+    assert_eq!(encode("right", TermMode::empty()), b"\x1b[C");
+    assert_eq!(encode("left", TermMode::empty()), b"\x1b[D");
+    assert_eq!(encode("home", TermMode::empty()), b"\x1b[H");
+    assert_eq!(encode("end", TermMode::empty()), b"\x1b[F");
+  }
+
+  #[test]
+  fn cursor_keys_use_ss3_with_decckm() {
+    assert_eq!(cursor_key(b'A', TermMode::APP_CURSOR), b"\x1bOA");
+    assert_eq!(encode("up", TermMode::APP_CURSOR), b"\x1bOA");
+    assert_eq!(encode("down", TermMode::APP_CURSOR), b"\x1bOB");
+    assert_eq!(encode("right", TermMode::APP_CURSOR), b"\x1bOC");
+    assert_eq!(encode("left", TermMode::APP_CURSOR), b"\x1bOD");
+    assert_eq!(encode("home", TermMode::APP_CURSOR), b"\x1bOH");
+    assert_eq!(encode("end", TermMode::APP_CURSOR), b"\x1bOF");
+  }
+
+  #[test]
+  fn other_keys_ignore_decckm() {
+    assert_eq!(encode("pageup", TermMode::APP_CURSOR), b"\x1b[5~");
+    assert_eq!(encode("enter", TermMode::APP_CURSOR), b"\r");
+  }
+
+  #[test]
+  fn wheel_scrolls_history_outside_alternate_scroll() {
+    let delta = ScrollDelta::Lines(point(0., 1.));
+    assert_eq!(
+      alternate_scroll_input(1, &delta, 3, TermMode::empty()),
+      None
+    );
+    assert_eq!(
+      alternate_scroll_input(1, &delta, 3, TermMode::ALT_SCREEN),
+      None
+    );
+    assert_eq!(
+      alternate_scroll_input(1, &delta, 3, TermMode::ALTERNATE_SCROLL),
+      None
+    );
+  }
+
+  #[test]
+  fn wheel_notches_send_repeated_arrow_keys() {
+    let mode = TermMode::ALT_SCREEN | TermMode::ALTERNATE_SCROLL;
+    let delta = ScrollDelta::Lines(point(0., 1.));
+    assert_eq!(
+      alternate_scroll_input(1, &delta, 3, mode),
+      Some(b"\x1b[A\x1b[A\x1b[A".to_vec())
+    );
+    assert_eq!(
+      alternate_scroll_input(-2, &delta, 1, mode),
+      Some(b"\x1b[B\x1b[B".to_vec())
+    );
+    assert_eq!(
+      alternate_scroll_input(1, &delta, 1, mode | TermMode::APP_CURSOR),
+      Some(b"\x1bOA".to_vec())
+    );
+  }
+
+  #[test]
+  fn wheel_repeats_are_clamped() {
+    let mode = TermMode::ALT_SCREEN | TermMode::ALTERNATE_SCROLL;
+    let delta = ScrollDelta::Lines(point(0., 1.));
+    assert_eq!(
+      alternate_scroll_input(1, &delta, 0, mode),
+      Some(b"\x1b[A".to_vec())
+    );
+    assert_eq!(
+      alternate_scroll_input(1, &delta, 1000, mode).map(|data| data.len()),
+      Some(3 * MAX_ALTERNATE_SCROLL_LINES)
+    );
+  }
+
+  #[test]
+  fn trackpad_sends_one_key_per_line() {
+    let mode = TermMode::ALT_SCREEN | TermMode::ALTERNATE_SCROLL;
+    let delta = ScrollDelta::Pixels(point(px(0.), px(40.)));
+    assert_eq!(
+      alternate_scroll_input(2, &delta, 3, mode),
+      Some(b"\x1b[A\x1b[A".to_vec())
+    );
+  }
+}
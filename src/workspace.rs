@@ -1,9 +1,12 @@
 use std::sync::atomic::{AtomicU64, Ordering};
 
-use gpui::{AppContext, Entity, SharedString};
+use gpui::{AnyView, App, AppContext, Entity, SharedString, Window};
 use gpui_component::IconName;
+use serde_json::Value;
 
-use crate::terminal::{LocalPty, Terminal, TerminalSize};
+use crate::session::{SESSION_VERSION, Session, TabSession};
+use crate::tab_kind::{TabKindDescriptor, TabKinds};
+use crate::terminal::view::TERMINAL_TAB_KIND;
 
 /// Tab ID generator
 static TAB_ID_COUNTER: AtomicU64 = AtomicU64::new(1);
@@ -15,15 +18,6 @@ pub fn generate_tab_id() -> TabId {
   TabId(TAB_ID_COUNTER.fetch_add(1, Ordering::SeqCst))
 }
 
-/// Tab 类型
-#[derive(Clone)]
-pub enum TabType {
-  /// 终端 Tab
-  Terminal(Entity<Terminal>),
-  /// SFTP Tab (TODO: 实现)
-  Sftp,
-}
-
 /// Tab 状态（标题、图标等）
 #[derive(Clone)]
 pub struct TabState {
@@ -45,57 +39,37 @@ impl TabState {
 pub struct TabItem {
   pub id: TabId,
   pub state: Entity<TabState>,
-  pub tab_type: TabType,
+  /// Tab 类型
+  pub kind: &'static TabKindDescriptor,
+  /// Tab 内容视图
+  pub view: AnyView,
 }
 
 impl TabItem {
-  /// 创建一个新的 TabItem
-  pub fn new(
-    cx: &mut gpui::Context<Workspace>,
-    title: impl Into<SharedString>,
-    icon: IconName,
-    tab_type: TabType,
-  ) -> Self {
-    let state = cx.new(|_cx| TabState::new(title, icon));
+  /// 创建一个新的 TabItem，标题和图标由类型描述提供
+  pub fn new(kind: &'static TabKindDescriptor, view: AnyView, cx: &mut App) -> Self {
+    let title = (kind.title)(&view, cx);
+    let state = cx.new(|_cx| TabState::new(title, kind.icon.clone()));
 
     Self {
       id: generate_tab_id(),
       state,
-      tab_type,
+      kind,
+      view,
     }
   }
 
-  /// 创建一个新的 Terminal Tab
-  ///
-  /// # Returns
-  /// * `Ok(Self)` - 成功创建 Tab
-  /// * `Err(String)` - 创建失败，返回错误信息
-  pub fn new_terminal(
-    cx: &mut gpui::Context<Workspace>,
-    rows: usize,
-    cols: usize,
-  ) -> Result<Self, String> {
-    // 创建本地 PTY
-    let size = TerminalSize::new(rows as u16, cols as u16, 0, 0);
-    let pty = LocalPty::new(size, None).map_err(|e| format!("Failed to create PTY: {}", e))?;
-
-    // 创建 Terminal Entity
-    let terminal_entity =
-      cx.new(|cx| Terminal::new(Box::new(pty), cx).expect("Failed to create terminal"));
-
-    Ok(Self {
-      id: generate_tab_id(),
-      state: cx.new(|_cx| TabState::new("Terminal", IconName::File)),
-      tab_type: TabType::Terminal(terminal_entity),
-    })
+  /// 内容视图为 `V` 时返回它
+  pub fn downcast<V: 'static>(&self) -> Option<Entity<V>> {
+    self.view.clone().downcast().ok()
   }
 
-  /// 创建一个新的 SFTP Tab
-  pub fn new_sftp(cx: &mut gpui::Context<Workspace>) -> Self {
-    Self {
-      id: generate_tab_id(),
-      state: cx.new(|_cx| TabState::new("SFTP", IconName::Folder)),
-      tab_type: TabType::Sftp,
+  /// 悬停提示文本：完整标题，以及类型提供的附加信息（文件路径、连接状态等）
+  pub fn tooltip(&self, cx: &App) -> SharedString {
+    let title = self.state.read(cx).title.clone();
+    match (self.kind.detail)(&self.view, cx) {
+      Some(detail) => format!("{}\n{}", title, detail).into(),
+      None => title,
     }
   }
 }
@@ -111,9 +85,9 @@ pub struct Workspace {
 impl Workspace {
   /// 创建一个新的 Workspace
   /// 如果没有 Tab，会自动创建一个默认的 Terminal Tab
-  pub fn new(cx: &mut gpui::Context<Self>) -> Self {
+  pub fn new(window: &mut Window, cx: &mut gpui::Context<Self>) -> Self {
     // 创建一个默认的 Terminal Tab
-    let tabs = match TabItem::new_terminal(cx, 24, 80) {
+    let tabs = match Self::build_tab(&TERMINAL_TAB_KIND, None, window, cx) {
       Ok(tab) => vec![tab],
       Err(e) => {
         eprintln!("Failed to create default terminal tab: {}", e);
@@ -129,16 +103,76 @@ impl Workspace {
     }
   }
 
+  /// 从保存的会话恢复 Tab；无法恢复的 Tab 会被跳过，全部失败时退回默认的 Terminal Tab
+  pub fn from_session(
+    session: &Session,
+    window: &mut Window,
+    cx: &mut gpui::Context<Self>,
+  ) -> Self {
+    let mut tabs = Vec::new();
+    let mut active_tab_id = None;
+    for (index, tab_session) in session.tabs.iter().enumerate() {
+      let tab = TabKinds::get(&tab_session.kind, cx)
+        .ok_or_else(|| format!("Unknown tab kind: {}", tab_session.kind))
+        .and_then(|kind| Self::build_tab(kind, Some(&tab_session.state), window, cx))
+        .inspect(|tab| {
+          if let Some(title) = &tab_session.title {
+            tab
+              .state
+              .update(cx, |state, _cx| state.title = title.clone().into());
+          }
+        });
+      match tab {
+        Ok(tab) => {
+          if session.active_tab == Some(index) {
+            active_tab_id = Some(tab.id);
+          }
+          tabs.push(tab);
+        }
+        Err(e) => eprintln!("Failed to restore tab: {}", e),
+      }
+    }
+
+    if tabs.is_empty() {
+      return Self::new(window, cx);
+    }
+    let active_tab_id = active_tab_id.or_else(|| tabs.first().map(|tab| tab.id));
+    Self {
+      tabs,
+      active_tab_id,
+    }
+  }
+
+  /// 当前 Tab 结构的会话快照
+  pub fn session(&self, cx: &gpui::App) -> Session {
+    let tabs = self
+      .tabs
+      .iter()
+      .map(|tab| TabSession {
+        kind: tab.kind.id.to_string(),
+        title: Some(tab.state.read(cx).title.to_string()),
+        state: (tab.kind.serialize)(&tab.view, cx),
+      })
+      .collect();
+
+    Session {
+      version: SESSION_VERSION,
+      tabs,
+      active_tab: self.active_index(),
+    }
+  }
+
   /// 添加一个新的 Tab
-  pub fn add_tab(&mut self, tab: TabItem) -> TabId {
+  pub fn add_tab(&mut self, tab: TabItem, cx: &mut gpui::Context<Self>) -> TabId {
     let id = tab.id;
     self.tabs.push(tab);
     self.active_tab_id = Some(id);
+    cx.notify();
     id
   }
 
   /// 关闭指定的 Tab
-  pub fn close_tab(&mut self, id: TabId) -> bool {
+  pub fn close_tab(&mut self, id: TabId, cx: &mut gpui::Context<Self>) -> bool {
     if let Some(index) = self.tabs.iter().position(|t| t.id == id) {
       self.tabs.remove(index);
 
@@ -146,15 +180,17 @@ impl Workspace {
       if self.active_tab_id == Some(id) {
         self.active_tab_id = self.tabs.get(index.saturating_sub(1)).map(|t| t.id);
       }
+      cx.notify();
       return true;
     }
     false
   }
 
   /// 激活指定的 Tab
-  pub fn activate_tab(&mut self, id: TabId) -> bool {
+  pub fn activate_tab(&mut self, id: TabId, cx: &mut gpui::Context<Self>) -> bool {
     if self.tabs.iter().any(|t| t.id == id) {
       self.active_tab_id = Some(id);
+      cx.notify();
       true
     } else {
       false
@@ -175,19 +211,30 @@ impl Workspace {
       .and_then(|id| self.tabs.iter().position(|t| t.id == id))
   }
 
-  /// 添加一个新的 Terminal Tab
+  /// 创建指定类型的 Tab
+  fn build_tab(
+    kind: &'static TabKindDescriptor,
+    state: Option<&Value>,
+    window: &mut Window,
+    cx: &mut App,
+  ) -> Result<TabItem, String> {
+    let view = (kind.build)(state, window, cx)?;
+    Ok(TabItem::new(kind, view, cx))
+  }
+
+  /// 创建并添加指定类型的 Tab
   ///
   /// # Returns
   /// * `Ok(TabId)` - 成功创建并添加 Tab
   /// * `Err(String)` - 创建失败，返回错误信息
-  pub fn add_terminal_tab(&mut self, cx: &mut gpui::Context<Self>) -> Result<TabId, String> {
-    let tab = TabItem::new_terminal(cx, 24, 80)?;
-    Ok(self.add_tab(tab))
-  }
-
-  /// 添加一个新的 SFTP Tab
-  pub fn add_sftp_tab(&mut self, cx: &mut gpui::Context<Self>) -> TabId {
-    let tab = TabItem::new_sftp(cx);
-    self.add_tab(tab)
+  pub fn open_tab(
+    &mut self,
+    kind: &'static TabKindDescriptor,
+    state: Option<&Value>,
+    window: &mut Window,
+    cx: &mut gpui::Context<Self>,
+  ) -> Result<TabId, String> {
+    let tab = Self::build_tab(kind, state, window, cx)?;
+    Ok(self.add_tab(tab, cx))
   }
 }